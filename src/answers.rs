@@ -0,0 +1,92 @@
+use std::{collections::HashMap, fmt, fs, io};
+
+use crate::{inputs::crate_relative, Answer};
+
+/// Known answers to the puzzles of one year.
+///
+/// They are read from `answers/{year}.toml`, or `answers/{profile}/{year}.toml`
+/// for the inputs of a profile (see [`crate::inputs::profile_dir`]), with a table per day
+/// and optional `part1` and `part2` keys:
+///
+/// ```toml
+/// [1]
+/// part1 = 516
+/// part2 = 71892
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    // (day, part) -> answer
+    expected: HashMap<(u8, u8), String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail { expected } => write!(f, "FAIL, expected {expected}"),
+            Status::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+impl Answers {
+    /// Load the answers of `year` for the inputs of `profile`. A missing
+    /// file means no known answers.
+    pub fn load(year: u16, profile: Option<&str>) -> Result<Self, String> {
+        let path = match profile {
+            Some(name) => format!("answers/{name}/{year}.toml"),
+            None => format!("answers/{year}.toml"),
+        };
+        let path = crate_relative(path);
+        let name = path.display();
+        match fs::read_to_string(&path) {
+            Ok(s) => Answers::parse(&s).map_err(|err| format!("{name}: {err}")),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(format!("{name}: {err}")),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let table: toml::Table = s.parse().map_err(|err| format!("{err}"))?;
+        let mut expected = HashMap::new();
+        for (day_key, parts) in table {
+            let day = day_key
+                .parse()
+                .map_err(|_| format!("Invalid day: {day_key}"))?;
+            let parts = parts
+                .as_table()
+                .ok_or(format!("Day {day} is not a table"))?;
+            for (part_key, value) in parts {
+                let part = match part_key.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => return Err(format!("Invalid key for day {day}: {part_key}")),
+                };
+                let answer = match value {
+                    toml::Value::Integer(n) => n.to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    _ => return Err(format!("Invalid answer for day {day} {part_key}")),
+                };
+                expected.insert((day, part), answer);
+            }
+        }
+        Ok(Answers { expected })
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Status {
+        match self.expected.get(&(day, part)) {
+            Some(expected) if *expected == answer.to_string() => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.clone(),
+            },
+            None => Status::Missing,
+        }
+    }
+}
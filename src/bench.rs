@@ -0,0 +1,200 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{parse::ParseError, Answer, Puzzle};
+
+/// Wall times of repeated runs of the same step.
+#[derive(Clone, Debug, Default)]
+pub struct Samples(Vec<Duration>);
+
+impl Samples {
+    pub fn push(&mut self, duration: Duration) {
+        self.0.push(duration);
+    }
+
+    pub fn runs(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn min(&self) -> Duration {
+        self.0.iter().copied().min().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.0.clone();
+        sorted.sort();
+        match sorted.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 1 => sorted[n / 2],
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        }
+    }
+}
+
+/// Timings of the steps of one puzzle.
+#[derive(Clone, Debug)]
+pub struct Timings {
+    pub year: u16,
+    pub day: u8,
+    pub parse: Samples,
+    pub parts: Vec<(u8, Samples)>,
+}
+
+impl Timings {
+    fn new(puzzle: &Puzzle, parts: &[u8]) -> Self {
+        Timings {
+            year: puzzle.year,
+            day: puzzle.day,
+            parse: Samples::default(),
+            parts: parts
+                .iter()
+                .map(|&part| (part, Samples::default()))
+                .collect(),
+        }
+    }
+
+    /// Name and samples of each step, in execution order.
+    pub fn steps(&self) -> impl Iterator<Item = (String, &Samples)> {
+        let parse = ("parse".to_string(), &self.parse);
+        let parts = self
+            .parts
+            .iter()
+            .map(|(part, samples)| (format!("part{part}"), samples));
+        std::iter::once(parse).chain(parts)
+    }
+
+    /// Sum of the medians of all steps.
+    pub fn total(&self) -> Duration {
+        self.steps().map(|(_, samples)| samples.median()).sum()
+    }
+}
+
+/// Run `parts` of `puzzle` once, calling `report` with each answer and the
+/// time taken to compute it as soon as it is available, then `repeat - 1`
+/// more times to collect timings.
+///
+/// Fails without running any part if the input cannot be parsed.
+pub fn run(
+    puzzle: &Puzzle,
+    input: &str,
+    parts: &[u8],
+    repeat: usize,
+    mut report: impl FnMut(u8, Answer, Duration),
+) -> Result<Timings, ParseError> {
+    let mut timings = Timings::new(puzzle, parts);
+    for i in 0..repeat.max(1) {
+        let input = input.to_string();
+        let start = Instant::now();
+        let parsed_input = puzzle.parse(input)?;
+        timings.parse.push(start.elapsed());
+
+        for (part, samples) in timings.parts.iter_mut() {
+            let start = Instant::now();
+            let answer = puzzle.solve(*part, parsed_input.as_ref());
+            let elapsed = start.elapsed();
+            samples.push(elapsed);
+            if i == 0 {
+                report(*part, answer, elapsed);
+            }
+        }
+    }
+    Ok(timings)
+}
+
+/// Human readable duration with 3 significant digits.
+pub struct Pretty(pub Duration);
+
+impl fmt::Display for Pretty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos() as f64;
+        let (value, unit) = match nanos {
+            n if n < 1e3 => (n, "ns"),
+            n if n < 1e6 => (n / 1e3, "µs"),
+            n if n < 1e9 => (n / 1e6, "ms"),
+            n => (n / 1e9, "s"),
+        };
+        let decimals = match value {
+            v if v < 10.0 => 2,
+            v if v < 100.0 => 1,
+            _ => 0,
+        };
+        let s = format!("{value:.decimals$}{unit}");
+        f.pad(&s)
+    }
+}
+
+/// Print a table with the median and minimum time of each step.
+pub fn print_summary(timings: &[Timings]) {
+    println!(
+        "{:>4} {:>3} {:>19} {:>19} {:>19} {:>9}",
+        "Year", "Day", "Parse", "Part 1", "Part 2", "Total"
+    );
+    for t in timings {
+        let cell = |part| match t.parts.iter().find(|(p, _)| *p == part) {
+            Some((_, samples)) => median_min(samples),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:>4} {:>3} {:>19} {:>19} {:>19} {:>9}",
+            t.year,
+            t.day,
+            median_min(&t.parse),
+            cell(1),
+            cell(2),
+            Pretty(t.total()).to_string(),
+        );
+    }
+    let total: Duration = timings.iter().map(|t| t.total()).sum();
+    println!("{:<5}{:>73}", "Total", Pretty(total).to_string());
+    if let Some(t) = timings.first().filter(|t| t.parse.runs() > 1) {
+        println!("(median / min over {} runs)", t.parse.runs());
+    }
+}
+
+fn median_min(samples: &Samples) -> String {
+    format!("{} / {}", Pretty(samples.median()), Pretty(samples.min()))
+}
+
+/// One line per step: `year,day,step,runs,median_ns,min_ns`.
+pub fn write_csv(timings: &[Timings], mut w: impl Write) -> io::Result<()> {
+    writeln!(w, "year,day,step,runs,median_ns,min_ns")?;
+    for t in timings {
+        for (step, samples) in t.steps() {
+            writeln!(
+                w,
+                "{},{},{},{},{},{}",
+                t.year,
+                t.day,
+                step,
+                samples.runs(),
+                samples.median().as_nanos(),
+                samples.min().as_nanos(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Same records as [`write_csv`], as a JSON array of objects.
+pub fn write_json(timings: &[Timings], mut w: impl Write) -> io::Result<()> {
+    let records: Vec<_> = timings
+        .iter()
+        .flat_map(|t| {
+            t.steps().map(|(step, samples)| {
+                format!(
+                    r#"  {{"year": {}, "day": {}, "step": "{}", "runs": {}, "median_ns": {}, "min_ns": {}}}"#,
+                    t.year,
+                    t.day,
+                    step,
+                    samples.runs(),
+                    samples.median().as_nanos(),
+                    samples.min().as_nanos(),
+                )
+            })
+        })
+        .collect();
+    writeln!(w, "[\n{}\n]", records.join(",\n"))
+}
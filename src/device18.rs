@@ -1,85 +1,384 @@
-use std::str::FromStr;
-
-pub fn parse_input(input: String) -> (usize, Vec<Instr>) {
-    let mut lines = input.lines();
-    let ip = lines
-        .next()
-        .unwrap()
-        .trim_start_matches("#ip ")
-        .parse()
-        .unwrap();
-    let instructions = lines.map(|l| Instr::from_str(l).unwrap()).collect();
-    (ip, instructions)
+use std::{fmt, str::FromStr};
+
+use crate::parse::{self, Line, ParseError, Span};
+
+pub mod asm;
+pub mod cfg;
+pub mod compile;
+pub mod cycle;
+pub mod debugger;
+pub mod decompile;
+pub mod disasm;
+pub mod halting;
+pub mod opcodes;
+pub mod optimise;
+pub mod profile;
+pub mod symbolic;
+pub mod trace;
+
+/// Register bound to the instruction pointer, from a line `#ip <register>`.
+fn parse_ip(line: Line) -> Result<usize, ParseError> {
+    match line.span().strip_prefix("#ip ") {
+        Some(register) => match line.parse(register, "a register")? {
+            ip @ 0..=5 => Ok(ip),
+            _ => Err(line.error(register, "a register between 0 and 5")),
+        },
+        None => Err(line.invalid("\"#ip <register>\"")),
+    }
+}
+
+pub fn parse_input(input: String) -> Result<(usize, Vec<Instr>), ParseError> {
+    let mut lines = parse::lines(&input);
+    let ip = parse_ip(lines.expect("\"#ip <register>\"")?)?;
+    let instructions = lines
+        .map(|line| line.parse_with())
+        .collect::<Result<_, _>>()?;
+    Ok((ip, instructions))
+}
+
+/// How additions and multiplications overflowing a register behave.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// Overflows are faults
+    #[default]
+    Checked,
+    /// Results are truncated, modulo 2^64 on 64-bit targets
+    Wrapping,
+}
+
+impl Arithmetic {
+    fn add(self, a: usize, b: usize) -> Result<usize, FaultKind> {
+        match self {
+            Arithmetic::Checked => a.checked_add(b).ok_or(FaultKind::Overflow),
+            Arithmetic::Wrapping => Ok(a.wrapping_add(b)),
+        }
+    }
+
+    fn mul(self, a: usize, b: usize) -> Result<usize, FaultKind> {
+        match self {
+            Arithmetic::Checked => a.checked_mul(b).ok_or(FaultKind::Overflow),
+            Arithmetic::Wrapping => Ok(a.wrapping_mul(b)),
+        }
+    }
+}
+
+/// Why a [`Machine`] could not run an instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultKind {
+    /// An operand or the result is a register the machine does not have
+    InvalidRegister(usize),
+    /// The register bound to the instruction pointer does not exist, or the
+    /// instruction pointer overflowed
+    IpOutOfRange(usize),
+    /// An addition or multiplication overflowed with checked arithmetic
+    Overflow,
+    /// The machine already ran this many instructions
+    StepLimit(u64),
+}
+
+impl fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaultKind::InvalidRegister(r) => write!(f, "invalid register {r}"),
+            FaultKind::IpOutOfRange(ip) => write!(f, "instruction pointer out of range in r{ip}"),
+            FaultKind::Overflow => write!(f, "arithmetic overflow"),
+            FaultKind::StepLimit(limit) => write!(f, "step limit of {limit} instructions exceeded"),
+        }
+    }
+}
+
+/// Fault of a [`Machine`], with the number of the faulting instruction and
+/// the registers when it happened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fault<const N: usize> {
+    pub kind: FaultKind,
+    pub pc: usize,
+    pub registers: [usize; N],
+}
+
+impl<const N: usize> fmt::Display for Fault<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at instruction {} with registers {:?}",
+            self.kind, self.pc, self.registers
+        )
+    }
+}
+
+impl<const N: usize> std::error::Error for Fault<N> {}
+
+/// Instruction naming a register the machine does not have, found before
+/// running the program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Invalid {
+    /// Number of the instruction
+    pub pc: usize,
+    pub kind: FaultKind,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at instruction {}", self.kind, self.pc)
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+/// Check that a program with the instruction pointer bound to `ip` only
+/// names registers a machine with `N` registers has, reporting the first
+/// one it would fault on.
+pub fn check_registers<const N: usize>(ip: usize, instructions: &[Instr]) -> Result<(), Invalid> {
+    if ip >= N {
+        return Err(Invalid {
+            pc: 0,
+            kind: FaultKind::IpOutOfRange(ip),
+        });
+    }
+    for (pc, instr) in instructions.iter().enumerate() {
+        let [kind_a, kind_b] = instr.op.operands();
+        let fields = [
+            (kind_a, instr.a),
+            (kind_b, instr.b),
+            (Operand::Register, instr.c),
+        ];
+        if let Some(&(_, r)) = fields
+            .iter()
+            .find(|&&(kind, r)| kind == Operand::Register && r >= N)
+        {
+            return Err(Invalid {
+                pc,
+                kind: FaultKind::InvalidRegister(r),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Device with `N` registers running a program, optionally with the
+/// instruction pointer bound to a register.
+///
+/// Without a bound register the instructions run in sequence. With one, the
+/// register is set to the number of each instruction before it runs, and
+/// the next instruction is the one after the value it holds afterwards. The
+/// program halts when the instruction pointer leaves it.
+///
+/// The `try_` methods return a [`Fault`] when an instruction cannot run,
+/// the others panic with it.
+#[derive(Clone, Debug)]
+pub struct Machine<'a, const N: usize> {
+    pub registers: [usize; N],
+    /// Number of the next instruction
+    pub pointer: usize,
+    /// Number of instructions run
+    pub steps: u64,
+    pub arithmetic: Arithmetic,
+    ip: Option<usize>,
+    instructions: &'a [Instr],
+}
+
+impl<'a, const N: usize> Machine<'a, N> {
+    /// Machine about to run the first instruction, with cleared registers
+    /// and checked arithmetic.
+    pub fn new(ip: Option<usize>, instructions: &'a [Instr]) -> Self {
+        Machine {
+            registers: [0; N],
+            pointer: 0,
+            steps: 0,
+            arithmetic: Arithmetic::default(),
+            ip,
+            instructions,
+        }
+    }
+
+    pub fn ip(&self) -> Option<usize> {
+        self.ip
+    }
+
+    pub fn instructions(&self) -> &'a [Instr] {
+        self.instructions
+    }
+
+    /// Next instruction, unless the program halted.
+    pub fn next(&self) -> Option<&'a Instr> {
+        self.instructions.get(self.pointer)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pointer >= self.instructions.len()
+    }
+
+    /// Registers as the next instruction sees them, the bound register
+    /// holding its number.
+    pub fn registers_seen(&self) -> [usize; N] {
+        let mut registers = self.registers;
+        if let Some(ip) = self.ip.filter(|&ip| ip < N) {
+            registers[ip] = self.pointer;
+        }
+        registers
+    }
+
+    fn fault(&self, kind: FaultKind) -> Fault<N> {
+        Fault {
+            kind,
+            pc: self.pointer,
+            registers: self.registers,
+        }
+    }
+
+    /// Run the next instruction. Returns false if the program halted
+    /// instead.
+    pub fn try_step(&mut self) -> Result<bool, Fault<N>> {
+        let Some(instr) = self.next() else {
+            return Ok(false);
+        };
+        let next = match self.ip {
+            Some(ip) => {
+                if ip >= N {
+                    return Err(self.fault(FaultKind::IpOutOfRange(ip)));
+                }
+                self.registers[ip] = self.pointer;
+                instr
+                    .execute(&mut self.registers, self.arithmetic)
+                    .map_err(|kind| self.fault(kind))?;
+                self.arithmetic
+                    .add(self.registers[ip], 1)
+                    .map_err(|_| self.fault(FaultKind::IpOutOfRange(ip)))?
+            }
+            None => {
+                instr
+                    .execute(&mut self.registers, self.arithmetic)
+                    .map_err(|kind| self.fault(kind))?;
+                self.pointer + 1
+            }
+        };
+        self.pointer = next;
+        self.steps += 1;
+        Ok(true)
+    }
+
+    /// Run the program until `stop` returns true before an instruction, or
+    /// until it halts. Returns whether it was stopped. Fails if the machine
+    /// has run `limit` instructions in total before either happens.
+    pub fn try_run_until(
+        &mut self,
+        mut stop: impl FnMut(&Self) -> bool,
+        limit: Option<u64>,
+    ) -> Result<bool, Fault<N>> {
+        loop {
+            if stop(self) {
+                return Ok(true);
+            }
+            if let Some(limit) = limit.filter(|&limit| self.steps >= limit && !self.is_halted()) {
+                return Err(self.fault(FaultKind::StepLimit(limit)));
+            }
+            if !self.try_step()? {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Run the program until it halts, and return the number of instructions
+    /// run.
+    pub fn try_run(&mut self, limit: Option<u64>) -> Result<u64, Fault<N>> {
+        self.try_run_until(|_| false, limit)?;
+        Ok(self.steps)
+    }
+
+    pub fn step(&mut self) -> bool {
+        self.try_step().unwrap_or_else(|fault| panic!("{fault}"))
+    }
+
+    pub fn run(&mut self) -> u64 {
+        self.try_run(None).unwrap_or_else(|fault| panic!("{fault}"))
+    }
+
+    pub fn run_until(&mut self, stop: impl FnMut(&Self) -> bool) -> bool {
+        self.try_run_until(stop, None)
+            .unwrap_or_else(|fault| panic!("{fault}"))
+    }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Instr {
-    op: Op,
-    a: usize,
-    b: usize,
-    c: usize,
+    pub op: Op,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
 }
 
 impl Instr {
-    pub fn apply(&self, registers: &mut [usize; 6]) {
-        registers[self.c] = match self.op {
-            Op::Addr => registers[self.a] + registers[self.b],
-            Op::Addi => registers[self.a] + self.b,
-            Op::Mulr => registers[self.a] * registers[self.b],
-            Op::Muli => registers[self.a] * self.b,
-            Op::Banr => registers[self.a] & registers[self.b],
-            Op::Bani => registers[self.a] & self.b,
-            Op::Borr => registers[self.a] | registers[self.b],
-            Op::Bori => registers[self.a] | self.b,
-            Op::Setr => registers[self.a],
-            Op::Seti => self.a,
-            Op::Gtir => (self.a > registers[self.b]) as usize,
-            Op::Gtri => (registers[self.a] > self.b) as usize,
-            Op::Gtrr => (registers[self.a] > registers[self.b]) as usize,
-            Op::Eqir => (self.a == registers[self.b]) as usize,
-            Op::Eqri => (registers[self.a] == self.b) as usize,
-            Op::Eqrr => (registers[self.a] == registers[self.b]) as usize,
-        }
+    /// Run the instruction on `registers`, which are left unchanged if it
+    /// faults.
+    pub fn execute<const N: usize>(
+        &self,
+        registers: &mut [usize; N],
+        arithmetic: Arithmetic,
+    ) -> Result<(), FaultKind> {
+        let value = |kind, field: usize| match kind {
+            Operand::Register => registers
+                .get(field)
+                .copied()
+                .ok_or(FaultKind::InvalidRegister(field)),
+            Operand::Immediate => Ok(field),
+            Operand::Ignored => Ok(0),
+        };
+        let [kind_a, kind_b] = self.op.operands();
+        let (a, b) = (value(kind_a, self.a)?, value(kind_b, self.b)?);
+        let result = match self.op {
+            Op::Addr | Op::Addi => arithmetic.add(a, b)?,
+            Op::Mulr | Op::Muli => arithmetic.mul(a, b)?,
+            Op::Banr | Op::Bani => a & b,
+            Op::Borr | Op::Bori => a | b,
+            Op::Setr | Op::Seti => a,
+            Op::Gtir | Op::Gtri | Op::Gtrr => (a > b) as usize,
+            Op::Eqir | Op::Eqri | Op::Eqrr => (a == b) as usize,
+        };
+        let c = registers
+            .get_mut(self.c)
+            .ok_or(FaultKind::InvalidRegister(self.c))?;
+        *c = result;
+        Ok(())
+    }
+
+    /// Run the instruction with checked arithmetic, like a [`Machine`] by
+    /// default.
+    pub fn apply<const N: usize>(&self, registers: &mut [usize; N]) -> Result<(), FaultKind> {
+        self.execute(registers, Arithmetic::Checked)
     }
 }
 
 impl FromStr for Instr {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let [op, a, b, c] = s.split(' ').collect::<Vec<_>>().as_slice() {
-            let op = match *op {
-                "addr" => Op::Addr,
-                "addi" => Op::Addi,
-                "mulr" => Op::Mulr,
-                "muli" => Op::Muli,
-                "banr" => Op::Banr,
-                "bani" => Op::Bani,
-                "borr" => Op::Borr,
-                "bori" => Op::Bori,
-                "setr" => Op::Setr,
-                "seti" => Op::Seti,
-                "gtir" => Op::Gtir,
-                "gtri" => Op::Gtri,
-                "gtrr" => Op::Gtrr,
-                "eqir" => Op::Eqir,
-                "eqri" => Op::Eqri,
-                "eqrr" => Op::Eqrr,
-                _ => {
-                    println!("Unknown op: {op}");
-                    return Err(());
-                }
-            };
-            let a = a.parse().unwrap();
-            let b = b.parse().unwrap();
-            let c = c.parse().unwrap();
-            Ok(Instr { op, a, b, c })
-        } else {
-            Err(())
-        }
+        let line = Line::first(s);
+        Instr::from_fields(&line, &line.span().split(" ").collect::<Vec<_>>())
     }
 }
 
-enum Op {
+impl Instr {
+    /// Instruction from its fields `[op, a, b, c]`, spans of `line`.
+    fn from_fields(line: &Line, fields: &[Span]) -> Result<Self, ParseError> {
+        let [op, a, b, c] = fields else {
+            return Err(line.invalid("an instruction \"<opcode> <a> <b> <c>\""));
+        };
+        let op = Op::from_name(op.text).ok_or_else(|| line.error(*op, "an opcode"))?;
+        let a = line.parse(*a, "a number")?;
+        let b = line.parse(*b, "a number")?;
+        let c = line.parse(*c, "a number")?;
+        Ok(Instr { op, a, b, c })
+    }
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.op, self.a, self.b, self.c)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Op {
     Addr,
     Addi,
     Mulr,
@@ -97,3 +396,171 @@ enum Op {
     Eqri,
     Eqrr,
 }
+
+/// How an instruction uses its `a` or `b` field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Register,
+    Immediate,
+    Ignored,
+}
+
+impl Op {
+    pub const ALL: [Op; 16] = [
+        Op::Addr,
+        Op::Addi,
+        Op::Mulr,
+        Op::Muli,
+        Op::Banr,
+        Op::Bani,
+        Op::Borr,
+        Op::Bori,
+        Op::Setr,
+        Op::Seti,
+        Op::Gtir,
+        Op::Gtri,
+        Op::Gtrr,
+        Op::Eqir,
+        Op::Eqri,
+        Op::Eqrr,
+    ];
+
+    /// Mnemonic, as in the input.
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Addr => "addr",
+            Op::Addi => "addi",
+            Op::Mulr => "mulr",
+            Op::Muli => "muli",
+            Op::Banr => "banr",
+            Op::Bani => "bani",
+            Op::Borr => "borr",
+            Op::Bori => "bori",
+            Op::Setr => "setr",
+            Op::Seti => "seti",
+            Op::Gtir => "gtir",
+            Op::Gtri => "gtri",
+            Op::Gtrr => "gtrr",
+            Op::Eqir => "eqir",
+            Op::Eqri => "eqri",
+            Op::Eqrr => "eqrr",
+        }
+    }
+
+    /// Kinds of the `a` and `b` operands.
+    pub fn operands(self) -> [Operand; 2] {
+        use Operand::*;
+        match self {
+            Op::Addr | Op::Mulr | Op::Banr | Op::Borr | Op::Gtrr | Op::Eqrr => [Register, Register],
+            Op::Addi | Op::Muli | Op::Bani | Op::Bori | Op::Gtri | Op::Eqri => {
+                [Register, Immediate]
+            }
+            Op::Gtir | Op::Eqir => [Immediate, Register],
+            Op::Setr => [Register, Ignored],
+            Op::Seti => [Immediate, Ignored],
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Op> {
+        Op::ALL.into_iter().find(|op| op.name() == name)
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instr_round_trip() {
+        for op in Op::ALL {
+            let instr = Instr {
+                op,
+                a: 1,
+                b: 20,
+                c: 3,
+            };
+            assert_eq!(instr.to_string().parse::<Instr>().unwrap(), instr);
+        }
+    }
+
+    #[test]
+    fn invalid_instr() {
+        let err = parse_input("#ip 0\nseti 5 0 1\nmovr 1 2 3\n".to_string()).unwrap_err();
+        assert_eq!(err, ParseError::new(3, 1, "movr", "an opcode"));
+    }
+
+    #[test]
+    fn machine_with_ip() {
+        // Example of day 19
+        let (ip, instructions) = parse_input(
+            "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\nsetr 1 0 0\nseti 8 0 4\nseti 9 0 5\n"
+                .to_string(),
+        )
+        .unwrap();
+        let mut machine = Machine::<6>::new(Some(ip), &instructions);
+        assert!(machine.run_until(|m| m.pointer == 4));
+        assert_eq!(machine.steps, 3);
+        assert_eq!(machine.run(), 5);
+        assert!(machine.is_halted());
+        assert_eq!(machine.registers, [6, 5, 6, 0, 0, 9]);
+        assert!(!machine.step());
+    }
+
+    #[test]
+    fn machine_without_ip() {
+        let instructions: Vec<Instr> = ["seti 3 0 0", "muli 0 7 1", "gtri 1 20 2"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let mut machine = Machine::<4>::new(None, &instructions);
+        assert!(!machine.run_until(|m| m.registers[3] != 0));
+        assert_eq!(machine.registers, [3, 21, 1, 0]);
+        assert_eq!(machine.pointer, 3);
+    }
+
+    #[test]
+    fn machine_faults() {
+        let program =
+            |lines: &[&str]| -> Vec<Instr> { lines.iter().map(|s| s.parse().unwrap()).collect() };
+        let overflow = program(&["seti 2 0 0", "muli 0 9223372036854775808 0", "addi 0 1 1"]);
+        let mut machine = Machine::<2>::new(None, &overflow);
+        let fault = machine.try_run(None).unwrap_err();
+        assert_eq!(fault.kind, FaultKind::Overflow);
+        assert_eq!((fault.pc, fault.registers), (1, [2, 0]));
+        assert_eq!(
+            fault.to_string(),
+            "arithmetic overflow at instruction 1 with registers [2, 0]"
+        );
+        let mut machine = Machine::<2>::new(None, &overflow);
+        machine.arithmetic = Arithmetic::Wrapping;
+        assert_eq!(machine.try_run(None), Ok(3));
+        assert_eq!(machine.registers, [0, 1]);
+        let mut registers = [2, 0];
+        assert_eq!(overflow[1].apply(&mut registers), Err(FaultKind::Overflow));
+        assert_eq!(registers, [2, 0]);
+
+        let invalid = program(&["setr 4 0 0"]);
+        let mut machine = Machine::<4>::new(None, &invalid);
+        assert_eq!(
+            machine.try_step().unwrap_err().kind,
+            FaultKind::InvalidRegister(4)
+        );
+        let mut machine = Machine::<4>::new(Some(4), &invalid);
+        assert_eq!(
+            machine.try_step().unwrap_err().kind,
+            FaultKind::IpOutOfRange(4)
+        );
+
+        let forever = program(&["addi 1 1 1", "seti 0 0 0"]);
+        let mut machine = Machine::<2>::new(Some(0), &forever);
+        let fault = machine.try_run(Some(10)).unwrap_err();
+        assert_eq!(fault.kind, FaultKind::StepLimit(10));
+        assert_eq!(machine.steps, 10);
+    }
+}
@@ -0,0 +1,499 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::{Instr, Op, Operand};
+use crate::parse::{self, Line, ParseError, Span};
+
+/// Macros, with the number of instructions they are lowered to.
+const MACROS: [(&str, usize); 8] = [
+    ("jmp", 1),
+    ("jeq", 4),
+    ("jne", 3),
+    ("jgt", 4),
+    ("jle", 3),
+    ("jz", 4),
+    ("jnz", 3),
+    ("halt", 1),
+];
+
+/// Register a conditional jump compares into.
+const SCRATCH: &str = "tmp";
+
+/// Assemble a device program written with labels, named registers, comments
+/// and jump macros:
+///
+/// ```text
+/// ; r0 = 1 + 2 + ... + n
+/// #ip 4             ; optional, else the last register the program leaves free
+/// .reg sum r0
+/// .reg i r1
+/// .reg tmp r2       ; clobbered by conditional jumps
+/// .reg n r5
+///         seti 5 0 n
+/// loop:   addi i 1 i
+///         addr sum i sum
+///         jne i n loop
+///         halt
+/// ```
+///
+/// Register operands are names, `r0` to `r5` or numbers, the register bound
+/// to the instruction pointer being named `ip`. Immediate operands are
+/// numbers or labels, optionally `+` or `-` a number.
+///
+/// The macros are `jmp L`, `jeq A B L`, `jne A B L`, `jgt A B L`,
+/// `jle A B L`, `jz R L`, `jnz R L` and `halt`, where `A` and `B` are
+/// registers or immediates, not both immediates. As their operands can be
+/// either, registers are only names and `r0` to `r5` there: numbers are
+/// immediates.
+pub fn assemble(source: &str) -> Result<(usize, Vec<Instr>), ParseError> {
+    let mut ip = None;
+    let mut names = HashMap::new();
+    let mut labels = HashMap::new();
+    let mut statements = Vec::new();
+    let mut len = 0;
+    for line in parse::lines(source) {
+        let text = line.span().split(";").next().unwrap();
+        let mut fields: Vec<_> = text.split_whitespace().collect();
+        while let Some(label) = fields.first().and_then(|f| f.strip_suffix(":")) {
+            if !is_name(label.text) {
+                return Err(line.error(label, "a label"));
+            }
+            if labels.insert(label.text, len).is_some() {
+                return Err(line.error(label, "a new label"));
+            }
+            fields.remove(0);
+        }
+        match fields[..] {
+            [] => (),
+            [Span { text: "#ip", .. }, register] => {
+                if ip.is_some() {
+                    return Err(line.error(fields[0], "a single #ip"));
+                }
+                ip = Some(register_number(&line, register)?);
+            }
+            [Span { text: "#ip", .. }, ..] => return Err(line.invalid("\"#ip <register>\"")),
+            [Span { text: ".reg", .. }, name, register] => {
+                if !is_name(name.text) || name.text == "ip" || register_number(&line, name).is_ok()
+                {
+                    return Err(line.error(name, "a register name"));
+                }
+                if names
+                    .insert(name.text, register_number(&line, register)?)
+                    .is_some()
+                {
+                    return Err(line.error(name, "a new register name"));
+                }
+            }
+            [Span { text: ".reg", .. }, ..] => {
+                return Err(line.invalid("\".reg <name> <register>\""))
+            }
+            [mnemonic, ..] => {
+                let size = match MACROS.iter().find(|(name, _)| *name == mnemonic.text) {
+                    Some(&(_, size)) => size,
+                    None if Op::from_name(mnemonic.text).is_some() => 1,
+                    None => {
+                        let expected = MACROS.map(|(name, _)| name).join(", ");
+                        return Err(line.error(mnemonic, format!("an opcode or {expected}")));
+                    }
+                };
+                statements.push((line, fields));
+                len += size;
+            }
+        }
+    }
+
+    let mut assembler = Assembler {
+        ip: 0,
+        names,
+        labels,
+        len,
+        instructions: Vec::with_capacity(len),
+    };
+    assembler.ip = match ip {
+        Some(ip) => ip,
+        None => assembler.free_register(&statements)?,
+    };
+    for (line, fields) in &statements {
+        assembler.statement(line, fields)?;
+    }
+    Ok((assembler.ip, assembler.instructions))
+}
+
+/// Program in the format read by [`parse_input`](super::parse_input).
+pub fn to_input(ip: usize, instructions: &[Instr]) -> String {
+    let mut input = format!("#ip {ip}\n");
+    for instr in instructions {
+        writeln!(input, "{instr}").unwrap();
+    }
+    input
+}
+
+fn is_name(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Register `rN` or `N`.
+fn register_number(line: &Line, s: Span) -> Result<usize, ParseError> {
+    match s.text.strip_prefix('r').unwrap_or(s.text).parse() {
+        Ok(r @ 0..=5) => Ok(r),
+        _ => Err(line.error(s, "a register between r0 and r5")),
+    }
+}
+
+/// Register `rN` of a macro operand, where a bare number is an immediate.
+fn macro_register_number(s: &str) -> Option<usize> {
+    s.strip_prefix('r')?.parse().ok().filter(|&r| r <= 5)
+}
+
+/// Operand of a conditional jump.
+#[derive(Clone, Copy)]
+enum Value {
+    Register(usize),
+    Immediate(usize),
+}
+
+struct Assembler<'a> {
+    ip: usize,
+    names: HashMap<&'a str, usize>,
+    labels: HashMap<&'a str, usize>,
+    /// Number of instructions of the program
+    len: usize,
+    instructions: Vec<Instr>,
+}
+
+impl Assembler<'_> {
+    /// The last register neither named nor used by the statements, to bind
+    /// to the instruction pointer.
+    fn free_register(&self, statements: &[(Line, Vec<Span>)]) -> Result<usize, ParseError> {
+        let mut used = [false; 6];
+        for &r in self.names.values() {
+            used[r] = true;
+        }
+        for (line, fields) in statements {
+            let (mnemonic, operands) = fields.split_first().unwrap();
+            let registers: Vec<_> = match Op::from_name(mnemonic.text) {
+                Some(op) => {
+                    let [a, b] = op.operands();
+                    let kinds = [a, b, Operand::Register];
+                    operands
+                        .iter()
+                        .zip(kinds)
+                        .filter(|(_, kind)| *kind == Operand::Register)
+                        .filter_map(|(&operand, _)| register_number(line, operand).ok())
+                        .collect()
+                }
+                // All but the label
+                None => operands
+                    .split_last()
+                    .map_or(&[][..], |(_, values)| values)
+                    .iter()
+                    .filter_map(|operand| macro_register_number(operand.text))
+                    .collect(),
+            };
+            for r in registers {
+                used[r] = true;
+            }
+        }
+        used.iter().rposition(|&used| !used).ok_or_else(|| {
+            let line = statements.last().map_or(Line::first(""), |(line, _)| *line);
+            line.invalid("a program leaving a register free for the instruction pointer, or #ip")
+        })
+    }
+
+    fn register(&self, line: &Line, s: Span) -> Result<usize, ParseError> {
+        match (s.text, self.names.get(s.text)) {
+            ("ip", _) => Ok(self.ip),
+            (_, Some(&r)) => Ok(r),
+            _ => register_number(line, s)
+                .map_err(|_| line.error(s, "a register name, or a register between r0 and r5")),
+        }
+    }
+
+    /// Number, or label optionally followed by `+N` or `-N`.
+    fn immediate(&self, line: &Line, s: Span) -> Result<usize, ParseError> {
+        if let Ok(n) = s.text.parse() {
+            return Ok(n);
+        }
+        let (label, offset) = match s.text.find(['+', '-']) {
+            Some(i) => s.text.split_at(i),
+            None => (s.text, ""),
+        };
+        let Some(&address) = self.labels.get(label) else {
+            return Err(line.error(s, "a number or a label"));
+        };
+        let value = match offset.split_at(offset.len().min(1)) {
+            ("", _) => Some(address),
+            ("+", n) => n.parse().ok().and_then(|n| address.checked_add(n)),
+            (_, n) => n.parse().ok().and_then(|n| address.checked_sub(n)),
+        };
+        value.ok_or_else(|| line.error(s, "a label plus or minus a number, at least 0"))
+    }
+
+    /// Operand of a macro: a register name, `rN`, else an immediate.
+    fn value(&self, line: &Line, s: Span) -> Result<Value, ParseError> {
+        let register = match (s.text, self.names.get(s.text)) {
+            ("ip", _) => Some(self.ip),
+            (_, Some(&r)) => Some(r),
+            _ => macro_register_number(s.text),
+        };
+        match register {
+            Some(r) => Ok(Value::Register(r)),
+            None => self
+                .immediate(line, s)
+                .map(Value::Immediate)
+                .map_err(|_| line.error(s, "a register, a number or a label")),
+        }
+    }
+
+    fn push(&mut self, op: Op, a: usize, b: usize, c: usize) {
+        self.instructions.push(Instr { op, a, b, c });
+    }
+
+    /// Jump to the instruction numbered `target`.
+    fn jump(&mut self, line: &Line, label: Span) -> Result<(), ParseError> {
+        let target = self.immediate(line, label)?;
+        // The instruction pointer is incremented after the instruction
+        let Some(value) = target.checked_sub(1) else {
+            return Err(line.error(label, "a target after the first instruction"));
+        };
+        self.push(Op::Seti, value, 0, self.ip);
+        Ok(())
+    }
+
+    /// Compare `a` and `b`, not both immediates, into the scratch register
+    /// with `Eqrr` or `Gtrr` (`op`), or its variants with immediates.
+    fn compare(&mut self, line: &Line, op: Op, a: Value, b: Value) -> Result<(), ParseError> {
+        let Some(&scratch) = self.names.get(SCRATCH) else {
+            return Err(line.error(
+                line.span().trim(),
+                format!("a scratch register for conditional jumps, \".reg {SCRATCH} <register>\""),
+            ));
+        };
+        let (op, a, b) = match (op, a, b) {
+            (_, Value::Immediate(_), Value::Immediate(_)) => {
+                unreachable!("comparison of two immediates")
+            }
+            (Op::Eqrr, Value::Register(a), Value::Register(b)) => (Op::Eqrr, a, b),
+            (Op::Eqrr, Value::Immediate(a), Value::Register(b)) => (Op::Eqir, a, b),
+            (Op::Eqrr, Value::Register(a), Value::Immediate(b)) => (Op::Eqri, a, b),
+            (_, Value::Register(a), Value::Register(b)) => (Op::Gtrr, a, b),
+            (_, Value::Immediate(a), Value::Register(b)) => (Op::Gtir, a, b),
+            (_, Value::Register(a), Value::Immediate(b)) => (Op::Gtri, a, b),
+        };
+        self.push(op, a, b, scratch);
+        Ok(())
+    }
+
+    /// Conditional jump to `label` if `a op b` holds, else if it does not
+    /// (`negated`).
+    fn branch(
+        &mut self,
+        line: &Line,
+        op: Op,
+        negated: bool,
+        [a, b]: [Value; 2],
+        label: Span,
+    ) -> Result<(), ParseError> {
+        let start = self.instructions.len();
+        self.compare(line, op, a, b)?;
+        let scratch = self.instructions[start].c;
+        // Skip the next instruction if the comparison holds
+        self.push(Op::Addr, scratch, self.ip, self.ip);
+        if !negated {
+            // Over the jump, to the end of the macro
+            self.push(Op::Seti, start + 3, 0, self.ip);
+        }
+        self.jump(line, label)
+    }
+
+    fn statement(&mut self, line: &Line, fields: &[Span]) -> Result<(), ParseError> {
+        let (mnemonic, operands) = fields.split_first().unwrap();
+        let expected = match (mnemonic.text, operands) {
+            ("jmp", &[label]) => return self.jump(line, label),
+            ("jeq" | "jne" | "jgt" | "jle", &[a, b, label]) => {
+                let (op, negated) = match mnemonic.text {
+                    "jeq" => (Op::Eqrr, false),
+                    "jne" => (Op::Eqrr, true),
+                    "jgt" => (Op::Gtrr, false),
+                    _ => (Op::Gtrr, true),
+                };
+                let values = [self.value(line, a)?, self.value(line, b)?];
+                if let [Value::Immediate(_), Value::Immediate(_)] = values {
+                    return Err(line.error(b, "a register, as the other operand is not"));
+                }
+                return self.branch(line, op, negated, values, label);
+            }
+            ("jz" | "jnz", &[r, label]) => {
+                let Value::Register(r) = self.value(line, r)? else {
+                    return Err(line.error(r, "a register"));
+                };
+                let values = [Value::Register(r), Value::Immediate(0)];
+                return self.branch(line, Op::Eqrr, mnemonic.text == "jnz", values, label);
+            }
+            ("halt", &[]) => {
+                // Past the end
+                self.push(Op::Seti, self.len, 0, self.ip);
+                return Ok(());
+            }
+            ("jmp", _) => "\"jmp <label>\"",
+            ("jeq" | "jne" | "jgt" | "jle", _) => "\"<macro> <a> <b> <label>\"",
+            ("jz" | "jnz", _) => "\"<macro> <register> <label>\"",
+            ("halt", _) => "\"halt\"",
+            (_, &[a, b, c]) => {
+                let op = Op::from_name(mnemonic.text).unwrap();
+                let [kind_a, kind_b] = op.operands();
+                let operand = |kind, s| match kind {
+                    Operand::Register => self.register(line, s),
+                    Operand::Immediate | Operand::Ignored => self.immediate(line, s),
+                };
+                let (a, b, c) = (
+                    operand(kind_a, a)?,
+                    operand(kind_b, b)?,
+                    self.register(line, c)?,
+                );
+                self.push(op, a, b, c);
+                return Ok(());
+            }
+            _ => "an instruction \"<opcode> <a> <b> <c>\"",
+        };
+        Err(line.error(line.span().trim(), expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::{parse_input, Machine};
+
+    const SUM: &str = "\
+; r0 = 1 + 2 + ... + n
+.reg sum r0
+.reg i r1
+.reg tmp r2
+.reg n r5
+        seti 5 0 n
+loop:   addi i 1 i
+        addr sum i sum
+        jne i n loop
+        halt
+";
+
+    #[test]
+    fn macros_and_default_ip() {
+        let (ip, instructions) = assemble(SUM).unwrap();
+        assert_eq!(
+            to_input(ip, &instructions),
+            "\
+#ip 4
+seti 5 0 5
+addi 1 1 1
+addr 0 1 0
+eqrr 1 5 2
+addr 2 4 4
+seti 0 0 4
+seti 7 0 4
+"
+        );
+        let mut machine = Machine::<6>::new(Some(ip), &instructions);
+        machine.run();
+        assert_eq!(machine.registers[0], 15);
+    }
+
+    #[test]
+    fn branches() {
+        // r0 counts the jumps taken
+        let program = "\
+#ip 3
+.reg tmp r2
+        seti 7 0 1
+        jeq r1 7 a
+        halt
+a:      addi r0 1 r0
+        jgt 8 r1 b
+        halt
+b:      addi r0 1 r0
+        jle r1 6 end
+        jz r1 end
+        jnz r1 c
+        halt
+c:      addi r0 1 r0
+end:
+";
+        let program = assemble(program).unwrap();
+        // Raw programs are read back unchanged
+        assert_eq!(
+            parse_input(to_input(program.0, &program.1)).unwrap(),
+            program
+        );
+        let mut machine = Machine::<6>::new(Some(program.0), &program.1);
+        machine.run();
+        assert_eq!(machine.registers[0], 3);
+        assert_eq!(machine.pointer, program.1.len());
+    }
+
+    #[test]
+    fn macro_immediates() {
+        // Numbers are immediates in macros, even 0 to 5, and do not use the
+        // registers with that number
+        let program = "\
+.reg tmp r2
+        jz r1 a
+        halt
+a:      seti 1 0 4
+        jnz r0 b
+        halt
+b:      addi 4 1 4
+        jeq r1 5 end
+        addi 4 1 4
+end:
+";
+        let (ip, instructions) = assemble(program).unwrap();
+        assert_eq!(ip, 5);
+        assert_eq!(instructions[0].to_string(), "eqri 1 0 2");
+        assert_eq!(instructions[11].to_string(), "eqri 1 5 2");
+        let mut machine = Machine::<6>::new(Some(ip), &instructions);
+        machine.registers[0] = 7;
+        machine.run();
+        assert_eq!(machine.registers[4], 3);
+
+        assert_eq!(
+            assemble(".reg tmp r2\njz 3 end\nend:\n").unwrap_err(),
+            ParseError::new(2, 4, "3", "a register")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let err = |source| assemble(source).unwrap_err();
+        assert_eq!(
+            err("a: seti 0 0 1\na: seti 0 0 1\n"),
+            ParseError::new(2, 1, "a", "a new label")
+        );
+        assert_eq!(
+            err("jmp nowhere\n"),
+            ParseError::new(1, 5, "nowhere", "a number or a label")
+        );
+        assert_eq!(
+            err("start: jmp start\n"),
+            ParseError::new(1, 12, "start", "a target after the first instruction")
+        );
+        assert_eq!(
+            err("seti 1 0 x\n"),
+            ParseError::new(
+                1,
+                10,
+                "x",
+                "a register name, or a register between r0 and r5"
+            )
+        );
+        assert_eq!(
+            err("jz r1 end\nend:\n").expected,
+            "a scratch register for conditional jumps, \".reg tmp <register>\""
+        );
+        assert_eq!(
+            err("#ip 7\n"),
+            ParseError::new(1, 5, "7", "a register between r0 and r5")
+        );
+        assert_eq!(err("nop\n").text, "nop");
+    }
+}
@@ -0,0 +1,385 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Write},
+};
+
+use super::{
+    disasm::{condition, constant, pseudo_code, relative_jump},
+    Instr,
+};
+
+/// Control-flow graph of a program, whose nodes are its basic blocks. Block
+/// 0 is the entry.
+pub struct Cfg<'a> {
+    pub ip: usize,
+    pub instructions: &'a [Instr],
+    pub blocks: Vec<Block>,
+}
+
+/// Instructions `start..end`, only entered at `start` and only left after
+/// `end - 1`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub successors: Vec<Target>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Target {
+    Block(usize),
+    /// The program halts
+    Exit,
+}
+
+/// Natural loop: the blocks that can reach one of its latches without going
+/// through its header, which dominates them all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loop {
+    pub header: usize,
+    /// Blocks jumping back to the header
+    pub latches: Vec<usize>,
+    /// Blocks of the loop, including the header
+    pub body: BTreeSet<usize>,
+}
+
+/// Instructions that can run after the instruction number `i`, where
+/// `instructions.len()` means that the program halts.
+///
+/// Writes to the ip register are jumps. A relative jump decided by a
+/// comparison (see [`condition`]) goes to one of the next two instructions,
+/// other computed jumps to any later instruction if relative, else anywhere.
+pub fn successors(ip: usize, instructions: &[Instr], i: usize) -> BTreeSet<usize> {
+    let len = instructions.len();
+    let instr = &instructions[i];
+    if instr.c != ip {
+        return BTreeSet::from([i + 1]);
+    }
+    if let Some(target) = constant(ip, instr, i) {
+        return BTreeSet::from([target.saturating_add(1).min(len)]);
+    }
+    if condition(ip, instructions, i).is_some() {
+        return BTreeSet::from([(i + 1).min(len), (i + 2).min(len)]);
+    }
+    match relative_jump(ip, instr, i) {
+        Some(_) => (i + 1..=len).collect(),
+        None => (0..=len).collect(),
+    }
+}
+
+impl<'a> Cfg<'a> {
+    pub fn new(ip: usize, instructions: &'a [Instr]) -> Self {
+        let len = instructions.len();
+        let successors: Vec<_> = (0..len).map(|i| successors(ip, instructions, i)).collect();
+
+        // Blocks start at the entry, at jump targets and after jumps
+        let mut leaders = BTreeSet::from([0]);
+        for (i, targets) in successors.iter().enumerate() {
+            if instructions[i].c == ip {
+                leaders.extend(targets);
+                leaders.insert(i + 1);
+            }
+        }
+        leaders.retain(|&i| i < len);
+        let starts: Vec<_> = leaders.into_iter().collect();
+
+        let mut block_of = vec![0; len];
+        for (b, &start) in starts.iter().enumerate() {
+            let end = starts.get(b + 1).copied().unwrap_or(len);
+            block_of[start..end].fill(b);
+        }
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(b, &start)| {
+                let end = starts.get(b + 1).copied().unwrap_or(len);
+                let successors = successors[end - 1]
+                    .iter()
+                    .map(|&i| match i < len {
+                        true => Target::Block(block_of[i]),
+                        false => Target::Exit,
+                    })
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                Block {
+                    start,
+                    end,
+                    successors,
+                }
+            })
+            .collect();
+
+        Cfg {
+            ip,
+            instructions,
+            blocks,
+        }
+    }
+
+    pub fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![Vec::new(); self.blocks.len()];
+        for (b, block) in self.blocks.iter().enumerate() {
+            for target in &block.successors {
+                if let Target::Block(s) = *target {
+                    predecessors[s].push(b);
+                }
+            }
+        }
+        predecessors
+    }
+
+    /// Whether each block can be reached from the entry.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![0];
+        while let Some(b) = stack.pop() {
+            if b >= self.blocks.len() || reachable[b] {
+                continue;
+            }
+            reachable[b] = true;
+            for target in &self.blocks[b].successors {
+                if let Target::Block(s) = *target {
+                    stack.push(s);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Blocks dominating each reachable block, i.e. on every path from the
+    /// entry to it (including itself). Unreachable blocks are dominated by
+    /// every block.
+    pub fn dominators(&self) -> Vec<BTreeSet<usize>> {
+        let n = self.blocks.len();
+        let predecessors = self.predecessors();
+        let reachable = self.reachable();
+        let all: BTreeSet<_> = (0..n).collect();
+        let mut dominators: Vec<_> = (0..n)
+            .map(|b| match b {
+                0 => BTreeSet::from([0]),
+                _ => all.clone(),
+            })
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for b in (1..n).filter(|&b| reachable[b]) {
+                let mut new = predecessors[b]
+                    .iter()
+                    .filter(|&&p| reachable[p])
+                    .map(|&p| dominators[p].clone())
+                    .reduce(|a, b| &a & &b)
+                    .unwrap_or_default();
+                new.insert(b);
+                if new != dominators[b] {
+                    dominators[b] = new;
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// Natural loops, by header. Loops with the same header are merged.
+    pub fn loops(&self) -> Vec<Loop> {
+        let predecessors = self.predecessors();
+        let reachable = self.reachable();
+        let dominators = self.dominators();
+
+        let mut loops: Vec<Loop> = Vec::new();
+        for (b, block) in self.blocks.iter().enumerate() {
+            if !reachable[b] {
+                continue;
+            }
+            for target in &block.successors {
+                let Target::Block(header) = *target else {
+                    continue;
+                };
+                if !dominators[b].contains(&header) {
+                    continue;
+                }
+                let i = match loops.iter().position(|l| l.header == header) {
+                    Some(i) => i,
+                    None => {
+                        loops.push(Loop {
+                            header,
+                            latches: Vec::new(),
+                            body: BTreeSet::from([header]),
+                        });
+                        loops.len() - 1
+                    }
+                };
+                let l = &mut loops[i];
+                l.latches.push(b);
+                let mut stack = vec![b];
+                while let Some(x) = stack.pop() {
+                    if l.body.insert(x) {
+                        stack.extend(predecessors[x].iter().filter(|&&p| reachable[p]));
+                    }
+                }
+            }
+        }
+        loops.sort_by_key(|l| l.header);
+        loops
+    }
+
+    /// Graph in the Graphviz DOT language, with the pseudo-code of each
+    /// block. Loop headers are bold and back edges dashed.
+    pub fn to_dot(&self) -> String {
+        let loops = self.loops();
+        let is_header = |b| loops.iter().any(|l| l.header == b);
+        let is_back_edge = |from, to| {
+            loops
+                .iter()
+                .any(|l| l.header == to && l.latches.contains(&from))
+        };
+
+        let mut dot = String::new();
+        dot += "digraph program {\n";
+        dot += "    node [shape=box, fontname=\"monospace\"];\n";
+        dot += "    entry [shape=point];\n";
+        dot += "    exit [shape=doublecircle, label=\"halt\"];\n";
+        if !self.blocks.is_empty() {
+            dot += "    entry -> b0;\n";
+        }
+        for (b, block) in self.blocks.iter().enumerate() {
+            let mut label = String::new();
+            for i in block.start..block.end {
+                let code = pseudo_code(self.ip, self.instructions, i);
+                write!(label, "{i}  {}\\l", escape(&code)).unwrap();
+            }
+            let style = if is_header(b) { ", style=bold" } else { "" };
+            writeln!(dot, "    b{b} [label=\"{label}\"{style}];").unwrap();
+        }
+        for (b, block) in self.blocks.iter().enumerate() {
+            for target in &block.successors {
+                match *target {
+                    Target::Block(s) if is_back_edge(b, s) => {
+                        writeln!(dot, "    b{b} -> b{s} [style=dashed];").unwrap()
+                    }
+                    Target::Block(s) => writeln!(dot, "    b{b} -> b{s};").unwrap(),
+                    Target::Exit => writeln!(dot, "    b{b} -> exit;").unwrap(),
+                }
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Block(b) => write!(f, "B{b}"),
+            Target::Exit => write!(f, "halt"),
+        }
+    }
+}
+
+/// Blocks with their instructions and successors, then loops.
+impl fmt::Display for Cfg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reachable = self.reachable();
+        for (b, block) in self.blocks.iter().enumerate() {
+            let range = match block.end - block.start {
+                1 => block.start.to_string(),
+                _ => format!("{}-{}", block.start, block.end - 1),
+            };
+            let successors: Vec<_> = block.successors.iter().map(|t| t.to_string()).collect();
+            let unreachable = if reachable[b] { "" } else { " (unreachable)" };
+            writeln!(
+                f,
+                "B{b:<3} {range:<7} -> {}{unreachable}",
+                successors.join(", ")
+            )?;
+        }
+        for l in self.loops() {
+            let body: Vec<_> = l.body.iter().map(|b| format!("B{b}")).collect();
+            let latches: Vec<_> = l.latches.iter().map(|b| format!("B{b}")).collect();
+            writeln!(
+                f,
+                "loop at B{}: {} (back from {})",
+                l.header,
+                body.join(", "),
+                latches.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    // Nested loops, as in day 19:
+    // for r1 in 1..=r5 { for r4 in 1..=r5 { if r1 * r4 == r5 { r0 += r1 } } }
+    const PROGRAM: &str = "\
+#ip 3
+seti 1 0 1
+seti 1 0 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 1 0 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 0 0 3
+mulr 3 3 3
+";
+
+    #[test]
+    fn blocks_and_loops() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let cfg = Cfg::new(ip, &instructions);
+        let starts: Vec<_> = cfg.blocks.iter().map(|b| b.start).collect();
+        assert_eq!(starts, [0, 1, 2, 5, 6, 7, 10, 11, 14, 15]);
+        assert_eq!(
+            cfg.blocks[2].successors,
+            [Target::Block(3), Target::Block(4)]
+        );
+        assert_eq!(cfg.blocks[9].successors, [Target::Exit]);
+
+        let loops = cfg.loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].header, 1);
+        assert_eq!(loops[0].latches, [8]);
+        assert_eq!(loops[0].body, (1..=8).collect());
+        assert_eq!(loops[1].header, 2);
+        assert_eq!(loops[1].latches, [6]);
+        assert_eq!(loops[1].body, BTreeSet::from([2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn computed_jump() {
+        let (ip, instructions) =
+            parse_input("#ip 0\naddr 0 1 0\nseti 1 0 1\n".to_string()).unwrap();
+        assert_eq!(successors(ip, &instructions, 0), BTreeSet::from([1, 2]));
+        let (ip, instructions) =
+            parse_input("#ip 0\nsetr 1 0 0\nseti 1 0 1\n".to_string()).unwrap();
+        assert_eq!(successors(ip, &instructions, 0), BTreeSet::from([0, 1, 2]));
+    }
+
+    #[test]
+    fn dot() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let dot = Cfg::new(ip, &instructions).to_dot();
+        assert!(dot.starts_with("digraph program {\n"));
+        assert!(dot.contains("    b1 [label=\"1  r4 = 1\\l\", style=bold];\n"));
+        assert!(dot.contains("    b8 -> b1 [style=dashed];\n"));
+        assert!(dot.contains("    b9 -> exit;\n"));
+    }
+}
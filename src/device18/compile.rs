@@ -0,0 +1,301 @@
+use std::time::Instant;
+
+use super::{check_registers, Fault, FaultKind, Instr, Invalid, Machine, Op, Operand};
+use crate::bench::Samples;
+
+/// Instruction compiled to a closure updating the registers and returning
+/// the number of the next instruction, or why it could not run.
+type Code = Box<dyn Fn(&mut [usize; 6]) -> Result<usize, FaultKind>>;
+
+/// Program compiled to closures, each specialised for its opcode and
+/// operands.
+///
+/// Reads of the instruction pointer register are replaced by the number of
+/// the instruction and writes to it by jumps, so that the register is only
+/// updated when the program stops. Additions and multiplications are checked
+/// like [`Arithmetic::Checked`](super::Arithmetic::Checked).
+pub struct Compiled {
+    ip: usize,
+    code: Vec<Code>,
+}
+
+/// Where an operand comes from, once the instruction pointer register is
+/// replaced by the number of the instruction.
+#[derive(Clone, Copy)]
+enum Source {
+    Register(usize),
+    Number(usize),
+}
+
+impl Compiled {
+    /// Compile a program, checking its registers in the order a [`Machine`]
+    /// would fault on them.
+    pub fn new(ip: usize, instructions: &[Instr]) -> Result<Self, Invalid> {
+        check_registers::<6>(ip, instructions)?;
+        let code = instructions
+            .iter()
+            .enumerate()
+            .map(|(i, instr)| compile(ip, instr, i))
+            .collect();
+        Ok(Compiled { ip, code })
+    }
+
+    /// Run the program from the instruction `pointer` until it halts or
+    /// after `limit` instructions, leaving `pointer` at the next instruction,
+    /// like a [`Machine`]. Returns the number of instructions run, or the
+    /// fault of the instruction at `pointer`, with the registers as the
+    /// machine would leave them.
+    pub fn run(
+        &self,
+        pointer: &mut usize,
+        registers: &mut [usize; 6],
+        limit: u64,
+    ) -> Result<u64, Fault<6>> {
+        let mut steps = 0;
+        let mut next = *pointer;
+        while let Some(code) = self.code.get(next) {
+            if steps == limit {
+                break;
+            }
+            match code(registers) {
+                Ok(target) => next = target,
+                Err(kind) => {
+                    // Only a jump to usize::MAX overflows the pointer
+                    registers[self.ip] = match kind {
+                        FaultKind::IpOutOfRange(_) => usize::MAX,
+                        _ => next,
+                    };
+                    *pointer = next;
+                    return Err(Fault {
+                        kind,
+                        pc: next,
+                        registers: *registers,
+                    });
+                }
+            }
+            steps += 1;
+        }
+        // The last instruction run wrote its own number, or jumped
+        if steps > 0 {
+            registers[self.ip] = next - 1;
+        }
+        *pointer = next;
+        Ok(steps)
+    }
+}
+
+fn compile(ip: usize, instr: &Instr, i: usize) -> Code {
+    let source = |kind, field| match kind {
+        Operand::Register if field == ip => Source::Number(i),
+        Operand::Register => Source::Register(field),
+        Operand::Immediate => Source::Number(field),
+        Operand::Ignored => Source::Number(0),
+    };
+    let [kind_a, kind_b] = instr.op.operands();
+    let (a, b) = (source(kind_a, instr.a), source(kind_b, instr.b));
+    let c = (instr.c != ip).then_some(instr.c);
+    match instr.op {
+        Op::Addr | Op::Addi => binary(
+            |x, y| x.checked_add(y).ok_or(FaultKind::Overflow),
+            a,
+            b,
+            c,
+            ip,
+            i,
+        ),
+        Op::Mulr | Op::Muli => binary(
+            |x, y| x.checked_mul(y).ok_or(FaultKind::Overflow),
+            a,
+            b,
+            c,
+            ip,
+            i,
+        ),
+        Op::Banr | Op::Bani => binary(|x, y| Ok(x & y), a, b, c, ip, i),
+        Op::Borr | Op::Bori => binary(|x, y| Ok(x | y), a, b, c, ip, i),
+        Op::Setr | Op::Seti => binary(|x, _| Ok(x), a, b, c, ip, i),
+        Op::Gtir | Op::Gtri | Op::Gtrr => binary(|x, y| Ok((x > y) as usize), a, b, c, ip, i),
+        Op::Eqir | Op::Eqri | Op::Eqrr => binary(|x, y| Ok((x == y) as usize), a, b, c, ip, i),
+    }
+}
+
+/// Closure computing `f(a, b)` into the register `c`, or jumping to the
+/// instruction after `f(a, b)` if `c` is `None` (the instruction pointer
+/// `ip`), for the instruction number `i`.
+fn binary(
+    f: impl Fn(usize, usize) -> Result<usize, FaultKind> + 'static,
+    a: Source,
+    b: Source,
+    c: Option<usize>,
+    ip: usize,
+    i: usize,
+) -> Code {
+    use Source::{Number, Register};
+    let next = i + 1;
+    let jump = move |target: usize| target.checked_add(1).ok_or(FaultKind::IpOutOfRange(ip));
+    match (a, b, c) {
+        (Register(a), Register(b), Some(c)) => Box::new(move |r| {
+            r[c] = f(r[a], r[b])?;
+            Ok(next)
+        }),
+        (Register(a), Number(b), Some(c)) => Box::new(move |r| {
+            r[c] = f(r[a], b)?;
+            Ok(next)
+        }),
+        (Number(a), Register(b), Some(c)) => Box::new(move |r| {
+            r[c] = f(a, r[b])?;
+            Ok(next)
+        }),
+        (Number(a), Number(b), Some(c)) => Box::new(move |r| {
+            r[c] = f(a, b)?;
+            Ok(next)
+        }),
+        (Register(a), Register(b), None) => Box::new(move |r| jump(f(r[a], r[b])?)),
+        (Register(a), Number(b), None) => Box::new(move |r| jump(f(r[a], b)?)),
+        (Number(a), Register(b), None) => Box::new(move |r| jump(f(a, r[b])?)),
+        (Number(a), Number(b), None) => Box::new(move |_| jump(f(a, b)?)),
+    }
+}
+
+/// Times of a program run by the plain interpreter and compiled.
+pub struct Benchmark {
+    /// Instructions run each time
+    pub steps: u64,
+    pub interpreted: Samples,
+    pub compiled: Samples,
+}
+
+/// Run a program from cleared registers until it halts or after `limit`
+/// instructions, `repeat` times with each backend, checking that they end in
+/// the same state. Faults are errors.
+pub fn benchmark(
+    ip: usize,
+    instructions: &[Instr],
+    limit: u64,
+    repeat: usize,
+) -> Result<Benchmark, String> {
+    let compiled = Compiled::new(ip, instructions).map_err(|err| err.to_string())?;
+    let mut benchmark = Benchmark {
+        steps: 0,
+        interpreted: Samples::default(),
+        compiled: Samples::default(),
+    };
+    for _ in 0..repeat.max(1) {
+        let mut machine = Machine::<6>::new(Some(ip), instructions);
+        let start = Instant::now();
+        machine
+            .try_run_until(|m| m.steps == limit, None)
+            .map_err(|fault| fault.to_string())?;
+        benchmark.interpreted.push(start.elapsed());
+        let expected = (machine.pointer, machine.registers, machine.steps);
+
+        let (mut pointer, mut registers) = (0, [0; 6]);
+        let start = Instant::now();
+        let steps = compiled
+            .run(&mut pointer, &mut registers, limit)
+            .map_err(|fault| fault.to_string())?;
+        benchmark.compiled.push(start.elapsed());
+        if (pointer, registers, steps) != expected {
+            return Err(format!(
+                "The compiled program stopped at {pointer} with {registers:?} after {steps} \
+                 instructions, instead of {} with {:?} after {}",
+                expected.0, expected.1, expected.2
+            ));
+        }
+        benchmark.steps = steps;
+    }
+    Ok(benchmark)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    // for r1 in 1..=r5 { for r4 in 1..=r5 { if r1 * r4 == r5 { r0 += r1 } } }
+    const PROGRAM: &str = "\
+#ip 3
+seti 1 0 1
+seti 1 0 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 1 0 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 0 0 3
+mulr 3 3 3
+";
+
+    #[test]
+    fn same_as_interpreter() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let compiled = Compiled::new(ip, &instructions).unwrap();
+        for limit in [0, 1, 100, u64::MAX] {
+            let mut machine = Machine::new(Some(ip), &instructions);
+            machine.registers[5] = 36;
+            machine.run_until(|m| m.steps == limit);
+            let expected = (machine.pointer, machine.registers, machine.steps);
+            let (mut pointer, mut registers) = (0, [0, 0, 0, 0, 0, 36]);
+            let steps = compiled.run(&mut pointer, &mut registers, limit).unwrap();
+            assert_eq!((pointer, registers, steps), expected);
+        }
+    }
+
+    #[test]
+    fn every_op() {
+        for op in Op::ALL {
+            for (a, b, c) in [(1, 2, 3), (0, 4, 5), (5, 0, 1), (2, 2, 0)] {
+                let instructions = [Instr { op, a, b, c }];
+                let compiled = Compiled::new(0, &instructions).unwrap();
+                let mut machine = Machine::new(Some(0), &instructions);
+                machine.registers = [0, 7, 3, 9, 3, 1];
+                machine.step();
+                let mut registers = [0, 7, 3, 9, 3, 1];
+                compiled.run(&mut 0, &mut registers, 1).unwrap();
+                assert_eq!(registers, machine.registers, "{op} {a} {b} {c}");
+            }
+        }
+    }
+
+    #[test]
+    fn benchmark_checks_backends() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let benchmark = benchmark(ip, &instructions, 10, 2).unwrap();
+        assert_eq!(benchmark.steps, 10);
+        assert_eq!(benchmark.compiled.runs(), 2);
+    }
+
+    #[test]
+    fn faults() {
+        let (ip, instructions) = parse_input("#ip 5\naddr 9 0 0".to_string()).unwrap();
+        let invalid = Invalid {
+            pc: 0,
+            kind: FaultKind::InvalidRegister(9),
+        };
+        assert_eq!(Compiled::new(ip, &instructions).err(), Some(invalid));
+        assert_eq!(
+            benchmark(ip, &instructions, 10, 1).err().unwrap(),
+            "invalid register 9 at instruction 0"
+        );
+
+        // Same faults as the interpreter, with the same registers
+        for program in ["#ip 5\nmulr 0 0 1", "#ip 0\naddr 0 1 0"] {
+            let (ip, instructions) = parse_input(program.to_string()).unwrap();
+            let compiled = Compiled::new(ip, &instructions).unwrap();
+            let mut machine = Machine::new(Some(ip), &instructions);
+            machine.registers[0] = usize::MAX;
+            machine.registers[1] = usize::MAX;
+            let expected = machine.try_run(None).unwrap_err();
+            let mut registers = [usize::MAX, usize::MAX, 0, 0, 0, 0];
+            let fault = compiled.run(&mut 0, &mut registers, 10).unwrap_err();
+            assert_eq!(fault, expected, "{program}");
+        }
+    }
+}
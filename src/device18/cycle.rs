@@ -0,0 +1,144 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use super::{Fault, Machine};
+
+/// Values of a register each time a program reaches an instruction, until
+/// the registers there repeat: from then on the program goes round the
+/// same cycle forever.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Distinct values, in the order they were first seen
+    pub values: Vec<usize>,
+    /// Number of instructions run when each value was first seen
+    pub steps: Vec<u64>,
+    /// Number of times the instruction was reached before the cycle
+    pub start: usize,
+    /// Number of times the instruction is reached in each turn of the cycle
+    pub length: usize,
+}
+
+impl Cycle {
+    pub fn first(&self) -> Option<usize> {
+        self.values.first().copied()
+    }
+
+    /// The value seen last for the first time, e.g. the one a program
+    /// halting when a register equals it would take the longest to halt for.
+    pub fn last(&self) -> Option<usize> {
+        self.values.last().copied()
+    }
+}
+
+/// Registers of a machine each time it reaches some instructions, to tell
+/// when the program repeats itself.
+#[derive(Clone, Debug, Default)]
+pub struct Visits<const N: usize> {
+    /// Number of the first visit of each instruction with each registers
+    seen: HashMap<(usize, [usize; N]), usize>,
+}
+
+impl<const N: usize> Visits<N> {
+    /// Number of visits recorded.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Record a visit of the next instruction of `machine`, with the
+    /// registers it sees. If the program was already there with the same
+    /// registers, it goes round the same cycle forever: returns the number of
+    /// that visit instead.
+    pub fn visit(&mut self, machine: &Machine<N>) -> Option<usize> {
+        let arrival = self.seen.len();
+        match self.seen.entry((machine.pointer, machine.registers_seen())) {
+            Entry::Occupied(entry) => Some(*entry.get()),
+            Entry::Vacant(entry) => {
+                entry.insert(arrival);
+                None
+            }
+        }
+    }
+}
+
+/// Run `machine` with `step` (e.g. [`Machine::try_step`]) until its
+/// registers repeat at the instruction number `at`, recording the values
+/// of `register` there. `None` if the program halts first.
+pub fn find<'a, const N: usize>(
+    machine: &mut Machine<'a, N>,
+    mut step: impl FnMut(&mut Machine<'a, N>) -> Result<bool, Fault<N>>,
+    at: usize,
+    register: usize,
+) -> Result<Option<Cycle>, Fault<N>> {
+    let mut visits = Visits::default();
+    let mut values = HashSet::new();
+    let mut cycle = Cycle {
+        values: Vec::new(),
+        steps: Vec::new(),
+        start: 0,
+        length: 0,
+    };
+    loop {
+        if machine.pointer == at {
+            if let Some(start) = visits.visit(machine) {
+                cycle.start = start;
+                cycle.length = visits.len() - start;
+                return Ok(Some(cycle));
+            }
+            let value = machine.registers_seen()[register];
+            if values.insert(value) {
+                cycle.values.push(value);
+                cycle.steps.push(machine.steps);
+            }
+        }
+        if !step(machine)? {
+            return Ok(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    // r1 = (5 * r1 + 3) % 16 until r1 == r0, from r1 = 7
+    const PROGRAM: &str = "\
+#ip 5
+seti 7 0 1
+muli 1 5 1
+addi 1 3 1
+bani 1 15 1
+eqrr 1 0 2
+addr 2 5 5
+seti 0 0 5
+";
+
+    #[test]
+    fn values_until_cycle() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let mut machine = Machine::<6>::new(Some(ip), &instructions);
+        machine.registers[0] = 99;
+        let cycle = find(&mut machine, Machine::try_step, 4, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            cycle.values,
+            [6, 1, 8, 11, 10, 5, 12, 15, 14, 9, 0, 3, 2, 13, 4, 7]
+        );
+        assert_eq!((cycle.first(), cycle.last()), (Some(6), Some(7)));
+        assert_eq!((cycle.start, cycle.length), (0, 16));
+        assert_eq!(cycle.steps[..2], [4, 10]);
+    }
+
+    #[test]
+    fn halts() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let mut machine = Machine::<6>::new(Some(ip), &instructions);
+        machine.registers[0] = 11;
+        assert_eq!(find(&mut machine, Machine::try_step, 4, 1), Ok(None));
+        assert!(machine.is_halted());
+    }
+}
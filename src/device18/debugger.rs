@@ -0,0 +1,468 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use super::{disasm::pseudo_code, Fault, Instr, Machine};
+
+const HELP: &str = "\
+Commands:
+  s, step [N]        run N instructions (default: 1)
+  c, continue        run until a breakpoint, a watchpoint or the end, for at
+                     most 10^8 instructions
+  b, break I         stop before running instruction I
+  d, delete I        remove the breakpoint at instruction I
+  w, watch rR        stop after register R changes
+  unwatch rR         remove the watchpoint on register R
+  r, registers       show the registers and the next instruction
+  set rR V           set register R to V
+  counts             show how many times each instruction ran
+  info               list the breakpoints and watchpoints
+  reset              restart the program, keeping breakpoints and watchpoints
+  h, help            show this help
+  q, quit            leave the debugger
+An empty line repeats the previous command.";
+
+/// Number of instructions [`Debugger::run`] runs at most by default when
+/// continuing, so that it returns on programs that loop forever.
+pub const CONTINUE_LIMIT: u64 = 100_000_000;
+
+/// Device program being debugged, with its state and the number of times
+/// each instruction ran.
+pub struct Debugger<'a> {
+    ip: usize,
+    machine: Machine<'a, 6>,
+    counts: Vec<u64>,
+    /// Number of instructions run at most when continuing
+    limit: u64,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeSet<usize>,
+}
+
+/// Why the program stopped running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// Before running the instruction
+    Breakpoint(usize),
+    /// After the instruction number `at` changed the register
+    Watchpoint {
+        register: usize,
+        at: usize,
+        old: usize,
+        new: usize,
+    },
+    /// The instruction pointer left the program
+    Halted,
+    /// All the requested instructions ran
+    Done,
+    /// Continued for this many instructions without stopping otherwise
+    Limit(u64),
+    /// The next instruction cannot run
+    Fault(Fault<6>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Step(u64),
+    Continue,
+    Break(usize),
+    Delete(usize),
+    Watch(usize),
+    Unwatch(usize),
+    Registers,
+    Set(usize, usize),
+    Counts,
+    Info,
+    Reset,
+    Help,
+    Quit,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(ip: usize, instructions: &'a [Instr]) -> Self {
+        Debugger {
+            ip,
+            machine: Machine::new(Some(ip), instructions),
+            counts: vec![0; instructions.len()],
+            limit: CONTINUE_LIMIT,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+        }
+    }
+
+    pub fn machine(&self) -> &Machine<'a, 6> {
+        &self.machine
+    }
+
+    /// Number of times each instruction ran.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn set_register(&mut self, register: usize, value: usize) {
+        self.machine.registers[register] = value;
+        if register == self.ip {
+            self.machine.pointer = value;
+        }
+    }
+
+    /// Number of instructions to run at most when continuing, instead of
+    /// [`CONTINUE_LIMIT`].
+    pub fn set_limit(&mut self, limit: u64) {
+        self.limit = limit;
+    }
+
+    pub fn add_breakpoint(&mut self, i: usize) {
+        self.breakpoints.insert(i);
+    }
+
+    pub fn add_watchpoint(&mut self, register: usize) {
+        self.watchpoints.insert(register);
+    }
+
+    /// Back to the start of the program, with cleared registers and counts.
+    pub fn reset(&mut self) {
+        self.machine = Machine::new(Some(self.ip), self.machine.instructions());
+        self.counts.fill(0);
+    }
+
+    /// Run at most `steps` instructions, or continue until the program
+    /// halts if `None`, for at most the limit set with
+    /// [`Debugger::set_limit`]. Breakpoints are only checked before the
+    /// second instruction, so that running again from a breakpoint does not
+    /// stop straight away.
+    pub fn run(&mut self, steps: Option<u64>) -> Stop {
+        let (steps, done) = match steps {
+            Some(steps) => (steps, Stop::Done),
+            None => (self.limit, Stop::Limit(self.limit)),
+        };
+        let mut ran = 0;
+        loop {
+            let machine = &mut self.machine;
+            if machine.is_halted() {
+                return Stop::Halted;
+            }
+            if ran == steps {
+                return done;
+            }
+            if ran > 0 && self.breakpoints.contains(&machine.pointer) {
+                return Stop::Breakpoint(machine.pointer);
+            }
+            let at = machine.pointer;
+            let old = machine.registers;
+            if let Err(fault) = machine.try_step() {
+                return Stop::Fault(fault);
+            }
+            // The bound register shows the next instruction, unless halting
+            if !machine.is_halted() {
+                machine.registers[self.ip] = machine.pointer;
+            }
+            self.counts[at] += 1;
+            ran += 1;
+
+            let changed = self
+                .watchpoints
+                .iter()
+                .find(|&&r| machine.registers[r] != old[r]);
+            if let Some(&register) = changed {
+                return Stop::Watchpoint {
+                    register,
+                    at,
+                    old: old[register],
+                    new: machine.registers[register],
+                };
+            }
+        }
+    }
+
+    /// Read commands from `input` until it ends or a `quit`, writing their
+    /// output and a prompt before each command to `out`.
+    pub fn repl(&mut self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        let mut previous = None;
+        write!(out, "(dbg) ")?;
+        out.flush()?;
+        for line in input.lines() {
+            let line = line?;
+            let command = match line.trim() {
+                "" => previous.clone(),
+                line => match line.parse() {
+                    Ok(command) => Some(command),
+                    Err(err) => {
+                        writeln!(out, "{err}")?;
+                        None
+                    }
+                },
+            };
+            if let Some(command) = command {
+                if command == Command::Quit {
+                    return Ok(());
+                }
+                self.execute(&command, &mut out)?;
+                previous = Some(command);
+            }
+            write!(out, "(dbg) ")?;
+            out.flush()?;
+        }
+        writeln!(out)
+    }
+
+    fn execute(&mut self, command: &Command, mut out: impl Write) -> io::Result<()> {
+        let len = self.machine.instructions().len();
+        match *command {
+            Command::Step(n) => {
+                let stop = self.run(Some(n));
+                self.report(stop, out)?;
+            }
+            Command::Continue => {
+                let stop = self.run(None);
+                self.report(stop, out)?;
+            }
+            Command::Break(i) | Command::Delete(i) if i >= len => {
+                writeln!(out, "No instruction {i}: the program has {len}")?
+            }
+            Command::Break(i) => {
+                self.add_breakpoint(i);
+                writeln!(out, "Breakpoint at {}", self.line(i))?
+            }
+            Command::Delete(i) => match self.breakpoints.remove(&i) {
+                true => writeln!(out, "Deleted the breakpoint at {i}")?,
+                false => writeln!(out, "No breakpoint at {i}")?,
+            },
+            Command::Watch(r) => {
+                self.add_watchpoint(r);
+                writeln!(out, "Watching r{r} = {}", self.machine.registers[r])?
+            }
+            Command::Unwatch(r) => match self.watchpoints.remove(&r) {
+                true => writeln!(out, "Stopped watching r{r}")?,
+                false => writeln!(out, "r{r} is not watched")?,
+            },
+            Command::Registers => writeln!(out, "{}", self.state())?,
+            Command::Set(r, value) => {
+                self.set_register(r, value);
+                writeln!(out, "{}", self.state())?
+            }
+            Command::Counts => {
+                let width = self.counts.iter().max().unwrap_or(&0).to_string().len();
+                for (i, &count) in self.counts.iter().enumerate() {
+                    writeln!(out, "{count:>width$}  {}", self.line(i))?;
+                }
+                writeln!(out, "{} steps", self.machine.steps)?
+            }
+            Command::Info => {
+                let breakpoints: Vec<_> = self.breakpoints.iter().map(|i| i.to_string()).collect();
+                let watchpoints: Vec<_> =
+                    self.watchpoints.iter().map(|r| format!("r{r}")).collect();
+                writeln!(out, "Breakpoints: {}", breakpoints.join(" "))?;
+                writeln!(out, "Watchpoints: {}", watchpoints.join(" "))?
+            }
+            Command::Reset => {
+                self.reset();
+                writeln!(out, "{}", self.state())?
+            }
+            Command::Help => writeln!(out, "{HELP}")?,
+            Command::Quit => (),
+        }
+        Ok(())
+    }
+
+    fn report(&self, stop: Stop, mut out: impl Write) -> io::Result<()> {
+        match stop {
+            Stop::Breakpoint(i) => writeln!(out, "Breakpoint at {i}")?,
+            Stop::Watchpoint {
+                register,
+                at,
+                old,
+                new,
+            } => writeln!(out, "r{register} changed from {old} to {new} at {at}")?,
+            Stop::Halted => writeln!(out, "Halted after {} steps", self.machine.steps)?,
+            Stop::Done => (),
+            Stop::Limit(limit) => writeln!(out, "Stopped after {limit} instructions")?,
+            Stop::Fault(fault) => writeln!(out, "Fault: {}", fault.kind)?,
+        }
+        writeln!(out, "{}", self.state())
+    }
+
+    /// Instruction number `i` with its pseudo-code.
+    fn line(&self, i: usize) -> String {
+        let instructions = self.machine.instructions();
+        format!(
+            "{i}  {}  {}",
+            instructions[i],
+            pseudo_code(self.ip, instructions, i)
+        )
+    }
+
+    /// Registers, then the next instruction.
+    fn state(&self) -> String {
+        let registers: Vec<_> = self
+            .machine
+            .registers
+            .iter()
+            .enumerate()
+            .map(|(r, value)| format!("r{r}={value}"))
+            .collect();
+        let next = match self.machine.is_halted() {
+            true => format!("ip={} (halted)", self.machine.pointer),
+            false => self.line(self.machine.pointer),
+        };
+        format!("{}\n{next}", registers.join(" "))
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<_> = s.split_whitespace().collect();
+        fn number<T: FromStr>(word: &str) -> Result<T, String> {
+            word.parse()
+                .map_err(|_| format!("Expected a number, found \"{word}\""))
+        }
+        let register = |word: &str| match word.strip_prefix('r').map(str::parse) {
+            Some(Ok(r @ 0..=5)) => Ok(r),
+            _ => Err(format!("Expected a register r0 to r5, found \"{word}\"")),
+        };
+        match words[..] {
+            ["s" | "step"] => Ok(Command::Step(1)),
+            ["s" | "step", n] => Ok(Command::Step(number(n)?)),
+            ["c" | "continue"] => Ok(Command::Continue),
+            ["b" | "break", i] => Ok(Command::Break(number(i)?)),
+            ["d" | "delete", i] => Ok(Command::Delete(number(i)?)),
+            ["w" | "watch", r] => Ok(Command::Watch(register(r)?)),
+            ["unwatch", r] => Ok(Command::Unwatch(register(r)?)),
+            ["r" | "registers"] => Ok(Command::Registers),
+            ["set", r, value] => Ok(Command::Set(register(r)?, number(value)?)),
+            ["counts"] => Ok(Command::Counts),
+            ["info"] => Ok(Command::Info),
+            ["reset"] => Ok(Command::Reset),
+            ["h" | "help"] => Ok(Command::Help),
+            ["q" | "quit"] => Ok(Command::Quit),
+            _ => Err(format!("Invalid command: {s} (try help)")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::{parse_input, FaultKind};
+
+    // Example of day 19
+    const PROGRAM: &str = "\
+#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5
+";
+
+    #[test]
+    fn breakpoints_and_watchpoints() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let mut debugger = Debugger::new(ip, &instructions);
+        debugger.add_breakpoint(4);
+        assert_eq!(debugger.run(None), Stop::Breakpoint(4));
+        assert_eq!(debugger.machine().registers, [4, 5, 6, 0, 0, 0]);
+        assert_eq!(debugger.run(Some(1)), Stop::Done);
+        assert_eq!(debugger.machine().pointer, 6);
+        debugger.add_watchpoint(5);
+        assert_eq!(
+            debugger.run(None),
+            Stop::Watchpoint {
+                register: 5,
+                at: 6,
+                old: 0,
+                new: 9
+            }
+        );
+        assert_eq!(debugger.run(None), Stop::Halted);
+        assert_eq!(debugger.machine().registers, [6, 5, 6, 0, 0, 9]);
+        assert_eq!(debugger.machine().steps, 5);
+        assert_eq!(debugger.counts(), [1, 1, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn continue_limit() {
+        let (ip, instructions) =
+            parse_input("#ip 0\nseti 0 0 1\naddi 1 1 1\nseti 0 0 0\n".to_string()).unwrap();
+        let mut debugger = Debugger::new(ip, &instructions);
+        debugger.set_limit(10);
+        assert_eq!(debugger.run(None), Stop::Limit(10));
+        assert_eq!(debugger.machine().steps, 10);
+        assert_eq!(debugger.run(Some(3)), Stop::Done);
+        assert_eq!(debugger.run(None), Stop::Limit(10));
+        assert_eq!(debugger.machine().steps, 23);
+
+        let mut out = Vec::new();
+        debugger.repl("c\n".as_bytes(), &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+(dbg) Stopped after 10 instructions
+r0=1 r1=16 r2=0 r3=0 r4=0 r5=0
+1  addi 1 1 1  r1 = r1 + 1
+(dbg) \n"
+        );
+    }
+
+    #[test]
+    fn repl() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let mut debugger = Debugger::new(ip, &instructions);
+        let commands = "break 4\nc\nset r2 7\ns\n\nbreak 9\njump\nq\nc\n";
+        let mut out = Vec::new();
+        debugger.repl(commands.as_bytes(), &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+(dbg) Breakpoint at 4  setr 1 0 0  goto r1 + 1
+(dbg) Breakpoint at 4
+r0=4 r1=5 r2=6 r3=0 r4=0 r5=0
+4  setr 1 0 0  goto r1 + 1
+(dbg) r0=4 r1=5 r2=7 r3=0 r4=0 r5=0
+4  setr 1 0 0  goto r1 + 1
+(dbg) r0=6 r1=5 r2=7 r3=0 r4=0 r5=0
+6  seti 9 0 5  r5 = 9
+(dbg) Halted after 5 steps
+r0=6 r1=5 r2=7 r3=0 r4=0 r5=9
+ip=7 (halted)
+(dbg) No instruction 9: the program has 7
+(dbg) Invalid command: jump (try help)
+(dbg) "
+        );
+    }
+
+    #[test]
+    fn fault() {
+        let (ip, instructions) =
+            parse_input("#ip 0\nseti 1 0 1\nmulr 1 2 7\n".to_string()).unwrap();
+        let mut debugger = Debugger::new(ip, &instructions);
+        let stop = debugger.run(None);
+        assert_eq!(
+            stop,
+            Stop::Fault(Fault {
+                kind: FaultKind::InvalidRegister(7),
+                pc: 1,
+                registers: [1, 1, 0, 0, 0, 0]
+            })
+        );
+        assert_eq!(debugger.machine().pointer, 1);
+    }
+
+    #[test]
+    fn invalid_commands() {
+        assert_eq!("s 10".parse(), Ok(Command::Step(10)));
+        assert_eq!(
+            "set r6 1".parse::<Command>().unwrap_err(),
+            "Expected a register r0 to r5, found \"r6\""
+        );
+        assert_eq!(
+            "b x".parse::<Command>().unwrap_err(),
+            "Expected a number, found \"x\""
+        );
+    }
+}
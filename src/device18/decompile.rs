@@ -0,0 +1,938 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Write},
+};
+
+use super::{
+    cfg::{successors, Cfg, Target},
+    check_registers,
+    disasm::{condition, constant, relative_jump, values, Value},
+    Instr, Invalid, Op,
+};
+
+/// A program lifted to structured pseudo-code, with `if` and loops instead
+/// of jumps, reads of the instruction pointer register replaced by the
+/// number of the instruction, and comparisons only computed for the jump
+/// they decide folded into it:
+///
+/// ```text
+/// r5 = 123
+/// do {
+///     r5 = r5 & 456
+///     r5 = r5 == 72
+/// } while r5 != 1
+/// ```
+///
+/// Jumps that cannot be structured go to labelled sections of the program,
+/// each starting at an instruction. The pseudo-code is the [`Display`] of
+/// the program, [`Decompiled::to_rust`] gives a Rust function.
+///
+/// [`Display`]: fmt::Display
+pub struct Decompiled<'a> {
+    pub ip: usize,
+    pub instructions: &'a [Instr],
+    /// Code from the instruction starting each section, the first one from
+    /// instruction 0
+    pub sections: Vec<(usize, Vec<Stmt>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Term {
+    Register(usize),
+    Number(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Mul,
+    And,
+    Or,
+    Gt,
+    Le,
+    Eq,
+    Ne,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Term(Term),
+    Binary(Term, BinOp, Term),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Stmt {
+    /// `r = expr`, comparisons giving 0 or 1
+    Assign(usize, Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    Loop(Vec<Stmt>),
+    While(Expr, Vec<Stmt>),
+    DoWhile(Vec<Stmt>, Expr),
+    Break,
+    Continue,
+    /// Jump to the section starting at the instruction
+    Goto(usize),
+    /// Computed jump, to the instruction after the value of the expression
+    Jump(Expr),
+    /// The program halts, the instruction pointer being the number
+    Halt(usize),
+}
+
+impl From<Value> for Term {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Register(r) => Term::Register(r),
+            Value::Number(n) => Term::Number(n),
+        }
+    }
+}
+
+impl BinOp {
+    fn new(op: Op) -> Option<Self> {
+        match op {
+            Op::Addr | Op::Addi => Some(BinOp::Add),
+            Op::Mulr | Op::Muli => Some(BinOp::Mul),
+            Op::Banr | Op::Bani => Some(BinOp::And),
+            Op::Borr | Op::Bori => Some(BinOp::Or),
+            Op::Setr | Op::Seti => None,
+            Op::Gtir | Op::Gtri | Op::Gtrr => Some(BinOp::Gt),
+            Op::Eqir | Op::Eqri | Op::Eqrr => Some(BinOp::Eq),
+        }
+    }
+
+    fn is_comparison(self) -> bool {
+        matches!(self, BinOp::Gt | BinOp::Le | BinOp::Eq | BinOp::Ne)
+    }
+}
+
+impl Expr {
+    /// Opposite of a comparison.
+    fn negate(self) -> Self {
+        match self {
+            Expr::Binary(a, BinOp::Gt, b) => Expr::Binary(a, BinOp::Le, b),
+            Expr::Binary(a, BinOp::Le, b) => Expr::Binary(a, BinOp::Gt, b),
+            Expr::Binary(a, BinOp::Eq, b) => Expr::Binary(a, BinOp::Ne, b),
+            Expr::Binary(a, BinOp::Ne, b) => Expr::Binary(a, BinOp::Eq, b),
+            _ => unreachable!("{self} is not a comparison"),
+        }
+    }
+
+    fn reads(self, r: usize) -> bool {
+        match self {
+            Expr::Term(a) => a == Term::Register(r),
+            Expr::Binary(a, _, b) => a == Term::Register(r) || b == Term::Register(r),
+        }
+    }
+}
+
+/// Natural loop of [`Cfg::loops`], by instruction.
+struct Loop {
+    header: usize,
+    body: BTreeSet<usize>,
+    /// Where the loop is left to with `break`: its first exit that does not
+    /// halt, if any
+    follow: Option<usize>,
+}
+
+/// Where the code being structured is.
+#[derive(Clone, Default)]
+struct Context {
+    /// Instruction where the code joins the enclosing statement, e.g. after
+    /// an `if`
+    end: Option<usize>,
+    /// Enclosing loops, innermost last
+    loops: Vec<usize>,
+}
+
+/// How a jump to an instruction is structured.
+enum Transfer {
+    Stmt(Stmt),
+    /// Jump to the end of the enclosing statement
+    End,
+    /// Anything else: the code there is either inlined or a section
+    Other,
+}
+
+struct Structurer<'a> {
+    ip: usize,
+    instructions: &'a [Instr],
+    loops: Vec<Loop>,
+    /// Number of reachable instructions that can run before each one
+    predecessors: Vec<usize>,
+    /// Registers whose values can be read before being written, from each
+    /// instruction (and the end of the program, where all of them are)
+    live: Vec<u8>,
+    sections: BTreeSet<usize>,
+    /// Sections needed by the code structured so far
+    needed: BTreeSet<usize>,
+    /// Jump targets whose code was inlined, only once
+    inlined: BTreeSet<usize>,
+}
+
+fn bit(r: usize) -> u8 {
+    match r < 6 {
+        true => 1 << r,
+        false => 0,
+    }
+}
+
+impl<'a> Decompiled<'a> {
+    pub fn new(ip: usize, instructions: &'a [Instr]) -> Self {
+        let len = instructions.len();
+        let successors: Vec<_> = (0..len).map(|i| successors(ip, instructions, i)).collect();
+
+        let mut reachable = vec![false; len];
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            if i < len && !reachable[i] {
+                reachable[i] = true;
+                stack.extend(&successors[i]);
+            }
+        }
+        let mut predecessors = vec![0; len + 1];
+        for i in (0..len).filter(|&i| reachable[i]) {
+            for &s in &successors[i] {
+                predecessors[s] += 1;
+            }
+        }
+
+        let mut live = vec![0; len + 1];
+        live[len] = 0b11_1111 & !bit(ip);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in (0..len).rev() {
+                let instr = &instructions[i];
+                let out = successors[i].iter().fold(0, |out, &s| out | live[s]);
+                let (a, b) = values(ip, instr, i);
+                let uses = [Some(a), b]
+                    .into_iter()
+                    .flatten()
+                    .fold(0, |uses, value| match value {
+                        Value::Register(r) => uses | bit(r),
+                        Value::Number(_) => uses,
+                    });
+                let defs = if instr.c != ip { bit(instr.c) } else { 0 };
+                let new = uses | (out & !defs);
+                if new != live[i] {
+                    live[i] = new;
+                    changed = true;
+                }
+            }
+        }
+
+        let cfg = Cfg::new(ip, instructions);
+        let loops = cfg
+            .loops()
+            .into_iter()
+            .map(|l| {
+                let blocks = &cfg.blocks;
+                let body: BTreeSet<_> = l
+                    .body
+                    .iter()
+                    .flat_map(|&b| blocks[b].start..blocks[b].end)
+                    .collect();
+                let follow = l
+                    .body
+                    .iter()
+                    .flat_map(|&b| &blocks[b].successors)
+                    .filter_map(|target| match *target {
+                        Target::Block(s) => Some(blocks[s].start),
+                        Target::Exit => None,
+                    })
+                    .filter(|s| !body.contains(s))
+                    .min();
+                Loop {
+                    header: blocks[l.header].start,
+                    body,
+                    follow,
+                }
+            })
+            .collect();
+
+        let mut structurer = Structurer {
+            ip,
+            instructions,
+            loops,
+            predecessors,
+            live,
+            sections: BTreeSet::from([0]),
+            needed: BTreeSet::new(),
+            inlined: BTreeSet::new(),
+        };
+        // Structure again with the sections needed, until there are no new
+        // ones
+        loop {
+            structurer.needed.clear();
+            structurer.inlined.clear();
+            let sections: Vec<_> = structurer
+                .sections
+                .clone()
+                .into_iter()
+                .map(|start| (start, structurer.walk(start, &Context::default(), true)))
+                .collect();
+            if structurer.needed.is_subset(&structurer.sections) {
+                return Decompiled {
+                    ip,
+                    instructions,
+                    sections,
+                };
+            }
+            let needed = std::mem::take(&mut structurer.needed);
+            structurer.sections.extend(needed);
+        }
+    }
+}
+
+impl Structurer<'_> {
+    fn expression(&self, i: usize) -> Expr {
+        let instr = &self.instructions[i];
+        match (values(self.ip, instr, i), BinOp::new(instr.op)) {
+            ((a, Some(b)), Some(op)) => Expr::Binary(a.into(), op, b.into()),
+            ((a, _), _) => Expr::Term(a.into()),
+        }
+    }
+
+    /// Target of the instruction number `i`, if it is a jump to a constant.
+    fn constant_jump(&self, i: usize) -> Option<usize> {
+        let instr = self.instructions.get(i)?;
+        let target = constant(self.ip, instr, i).filter(|_| instr.c == self.ip)?;
+        Some(target.saturating_add(1))
+    }
+
+    fn transfer(&self, target: usize, ctx: &Context) -> Transfer {
+        if target >= self.instructions.len() {
+            return Transfer::Stmt(Stmt::Halt(target));
+        }
+        if let Some(&l) = ctx.loops.last() {
+            let l = &self.loops[l];
+            if target == l.header {
+                return Transfer::Stmt(Stmt::Continue);
+            }
+            if !l.body.contains(&target) && Some(target) == l.follow {
+                return Transfer::Stmt(Stmt::Break);
+            }
+        }
+        if Some(target) == ctx.end {
+            return Transfer::End;
+        }
+        Transfer::Other
+    }
+
+    /// Whether a jump to `target` can be structured as an `if` inside the
+    /// code of `ctx`.
+    fn inside(&self, target: usize, ctx: &Context) -> bool {
+        let in_loop = ctx
+            .loops
+            .last()
+            .is_none_or(|&l| self.loops[l].body.contains(&target));
+        in_loop && ctx.end.is_none_or(|end| target <= end)
+    }
+
+    /// Code jumping to `target`: inlined if this is its only entry, else a
+    /// section.
+    fn goto(&mut self, target: usize, ctx: &Context) -> Vec<Stmt> {
+        match self.transfer(target, ctx) {
+            Transfer::Stmt(stmt) => vec![stmt],
+            Transfer::End => Vec::new(),
+            Transfer::Other
+                if !self.sections.contains(&target)
+                    && self.predecessors[target] == 1
+                    && self.inlined.insert(target) =>
+            {
+                let ctx = Context {
+                    end: None,
+                    loops: ctx.loops.clone(),
+                };
+                self.walk(target, &ctx, false)
+            }
+            Transfer::Other => {
+                self.needed.insert(target);
+                vec![Stmt::Goto(target)]
+            }
+        }
+    }
+
+    /// Structured code from the instruction `i`, which is jumped to unless
+    /// it is the `entry` of a section or of the innermost loop.
+    fn walk(&mut self, mut i: usize, ctx: &Context, entry: bool) -> Vec<Stmt> {
+        let len = self.instructions.len();
+        let mut code = Vec::new();
+        let mut first = entry;
+        loop {
+            if !first {
+                match self.transfer(i, ctx) {
+                    Transfer::Stmt(stmt) => {
+                        code.push(stmt);
+                        return code;
+                    }
+                    Transfer::End => return code,
+                    Transfer::Other => (),
+                }
+                if self.sections.contains(&i) {
+                    self.needed.insert(i);
+                    code.push(Stmt::Goto(i));
+                    return code;
+                }
+            }
+            first = false;
+            if i >= len {
+                code.push(Stmt::Halt(i));
+                return code;
+            }
+
+            let l = (0..self.loops.len())
+                .find(|&l| self.loops[l].header == i && !ctx.loops.contains(&l));
+            if let Some(l) = l {
+                let mut inner = Context {
+                    end: None,
+                    loops: ctx.loops.clone(),
+                };
+                inner.loops.push(l);
+                let body = self.walk(i, &inner, true);
+                code.push(structure_loop(body));
+                match self.loops[l].follow {
+                    Some(follow) => i = follow,
+                    None => return code,
+                }
+                continue;
+            }
+
+            let instr = &self.instructions[i];
+            let paired = Some(i + 1) != ctx.end
+                && i + 1 < len
+                && condition(self.ip, self.instructions, i + 1) == Some(i);
+            if paired {
+                i = self.conditional(i + 1, ctx, &mut code);
+                continue;
+            }
+            if instr.c != self.ip {
+                code.push(Stmt::Assign(instr.c, self.expression(i)));
+                i += 1;
+                continue;
+            }
+            if let Some(target) = self.constant_jump(i) {
+                code.extend(self.goto(target, ctx));
+                return code;
+            }
+            let targets = match relative_jump(self.ip, instr, i) {
+                Some(_) => i + 1..len,
+                None => 0..len,
+            };
+            self.needed.extend(targets);
+            code.push(Stmt::Jump(self.expression(i)));
+            return code;
+        }
+    }
+
+    /// Structure the jump number `i` decided by the comparison before it,
+    /// going to `i + 2` if it holds, else to `i + 1`. Returns the instruction
+    /// to continue from.
+    fn conditional(&mut self, i: usize, ctx: &Context, code: &mut Vec<Stmt>) -> usize {
+        let len = self.instructions.len();
+        let r = self.instructions[i - 1].c;
+        let comparison = self.expression(i - 1);
+        let live = self.live[(i + 1).min(len)] | self.live[(i + 2).min(len)];
+        let cond = match live & bit(r) != 0 {
+            true => {
+                code.push(Stmt::Assign(r, comparison));
+                match comparison.reads(r) {
+                    true => Expr::Binary(Term::Register(r), BinOp::Eq, Term::Number(1)),
+                    false => comparison,
+                }
+            }
+            false => comparison,
+        };
+
+        // Usually the instruction after the jump jumps elsewhere
+        let target = self
+            .constant_jump(i + 1)
+            .filter(|_| self.predecessors[i + 1] == 1);
+        let Some(target) = target else {
+            // Else the jump skips it
+            let ctx_then = Context {
+                end: Some(i + 2),
+                loops: ctx.loops.clone(),
+            };
+            let then = self.walk(i + 1, &ctx_then, false);
+            code.push(Stmt::If(cond.negate(), then, Vec::new()));
+            return i + 2;
+        };
+        let next = i + 2;
+        match self.transfer(target, ctx) {
+            Transfer::Stmt(stmt) => {
+                code.push(Stmt::If(cond.negate(), vec![stmt], Vec::new()));
+                next
+            }
+            Transfer::End => {
+                let then = self.region(next, target, ctx);
+                code.push(Stmt::If(cond, then, Vec::new()));
+                target
+            }
+            Transfer::Other if target > next && self.inside(target, ctx) => {
+                // An `else` if the code run when the comparison holds ends
+                // with a jump over the code run otherwise
+                let over = self
+                    .constant_jump(target - 1)
+                    .filter(|_| target - 1 > next && self.predecessors[target - 1] == 1)
+                    .filter(|&end| end > target)
+                    .filter(|&end| match self.transfer(end, ctx) {
+                        Transfer::End => true,
+                        Transfer::Other => self.inside(end, ctx),
+                        Transfer::Stmt(_) => false,
+                    });
+                match over {
+                    Some(end) => {
+                        let then = self.region(next, target - 1, ctx);
+                        let otherwise = self.region(target, end, ctx);
+                        code.push(Stmt::If(cond, then, otherwise));
+                        end
+                    }
+                    None => {
+                        let then = self.region(next, target, ctx);
+                        code.push(Stmt::If(cond, then, Vec::new()));
+                        target
+                    }
+                }
+            }
+            Transfer::Other => {
+                let otherwise = self.goto(target, ctx);
+                code.push(Stmt::If(cond.negate(), otherwise, Vec::new()));
+                next
+            }
+        }
+    }
+
+    /// Structured code from `start`, joining the rest at `end`.
+    fn region(&mut self, start: usize, end: usize, ctx: &Context) -> Vec<Stmt> {
+        if start == end {
+            return Vec::new();
+        }
+        let ctx = Context {
+            end: Some(end),
+            loops: ctx.loops.clone(),
+        };
+        self.walk(start, &ctx, false)
+    }
+}
+
+/// Whether `continue` is used in `code`, outside nested loops.
+fn continues(code: &[Stmt]) -> bool {
+    code.iter().any(|stmt| match stmt {
+        Stmt::Continue => true,
+        Stmt::If(_, then, otherwise) => continues(then) || continues(otherwise),
+        _ => false,
+    })
+}
+
+/// Loop running `body` again and again, as a `while` or `do while` if it
+/// only exits at its start or end.
+fn structure_loop(mut body: Vec<Stmt>) -> Stmt {
+    if body.last() == Some(&Stmt::Continue) {
+        body.pop();
+    }
+    let n = body.len();
+    match &body[n.saturating_sub(2)..] {
+        [Stmt::If(cond, then, otherwise), Stmt::Break]
+            if then[..] == [Stmt::Continue]
+                && otherwise.is_empty()
+                && !continues(&body[..n - 2]) =>
+        {
+            let cond = *cond;
+            body.truncate(n - 2);
+            return Stmt::DoWhile(body, cond);
+        }
+        [.., Stmt::If(cond, then, otherwise)]
+            if then[..] == [Stmt::Break] && otherwise.is_empty() && !continues(&body) =>
+        {
+            let cond = cond.negate();
+            body.pop();
+            return Stmt::DoWhile(body, cond);
+        }
+        _ => (),
+    }
+    match body.first() {
+        Some(Stmt::If(cond, then, otherwise))
+            if then[..] == [Stmt::Break] && otherwise.is_empty() =>
+        {
+            let cond = cond.negate();
+            body.remove(0);
+            Stmt::While(cond, body)
+        }
+        _ => Stmt::Loop(body),
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Register(r) => write!(f, "r{r}"),
+            Term::Number(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinOp::Add => "+",
+            BinOp::Mul => "*",
+            BinOp::And => "&",
+            BinOp::Or => "|",
+            BinOp::Gt => ">",
+            BinOp::Le => "<=",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Term(a) => write!(f, "{a}"),
+            Expr::Binary(a, op, b) => write!(f, "{a} {op} {b}"),
+        }
+    }
+}
+
+/// Next instruction of a computed jump writing `expr` to the instruction
+/// pointer register.
+fn jump_target(expr: &Expr) -> String {
+    match *expr {
+        Expr::Binary(Term::Number(n), BinOp::Add, Term::Register(r))
+        | Expr::Binary(Term::Register(r), BinOp::Add, Term::Number(n)) => {
+            format!("{} + r{r}", n + 1)
+        }
+        _ => format!("{expr} + 1"),
+    }
+}
+
+impl Decompiled<'_> {
+    fn pseudo_code(out: &mut String, code: &[Stmt], depth: usize) {
+        let indent = "    ".repeat(depth);
+        for stmt in code {
+            match stmt {
+                Stmt::Assign(r, expr) => writeln!(out, "{indent}r{r} = {expr}"),
+                Stmt::If(cond, then, otherwise) => {
+                    writeln!(out, "{indent}if {cond} {{").unwrap();
+                    Self::pseudo_code(out, then, depth + 1);
+                    if !otherwise.is_empty() {
+                        writeln!(out, "{indent}}} else {{").unwrap();
+                        Self::pseudo_code(out, otherwise, depth + 1);
+                    }
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::Loop(body) => {
+                    writeln!(out, "{indent}loop {{").unwrap();
+                    Self::pseudo_code(out, body, depth + 1);
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::While(cond, body) => {
+                    writeln!(out, "{indent}while {cond} {{").unwrap();
+                    Self::pseudo_code(out, body, depth + 1);
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::DoWhile(body, cond) => {
+                    writeln!(out, "{indent}do {{").unwrap();
+                    Self::pseudo_code(out, body, depth + 1);
+                    writeln!(out, "{indent}}} while {cond}")
+                }
+                Stmt::Break => writeln!(out, "{indent}break"),
+                Stmt::Continue => writeln!(out, "{indent}continue"),
+                Stmt::Goto(target) => writeln!(out, "{indent}goto {target}"),
+                Stmt::Jump(expr) => writeln!(out, "{indent}goto {}", jump_target(expr)),
+                Stmt::Halt(_) => writeln!(out, "{indent}halt"),
+            }
+            .unwrap();
+        }
+    }
+
+    /// Registers of the program, as a Rust array, when it halts with the
+    /// instruction pointer at `pointer`.
+    fn rust_registers(&self, pointer: &str) -> String {
+        let registers: Vec<_> = (0..6)
+            .map(|r| match r == self.ip {
+                true => pointer.to_string(),
+                false => format!("r{r}"),
+            })
+            .collect();
+        format!("[{}]", registers.join(", "))
+    }
+
+    fn rust(&self, out: &mut String, code: &[Stmt], depth: usize) {
+        let indent = "    ".repeat(depth);
+        let dispatch = self.sections.len() > 1;
+        let halt = |pointer: usize| match pointer {
+            0 => self.rust_registers(&format!("registers[{}]", self.ip)),
+            _ => self.rust_registers(&(pointer - 1).to_string()),
+        };
+        for (n, stmt) in code.iter().enumerate() {
+            match stmt {
+                Stmt::Assign(r, Expr::Binary(a, op, b)) if op.is_comparison() => {
+                    writeln!(out, "{indent}r{r} = ({a} {op} {b}) as usize;")
+                }
+                Stmt::Assign(r, expr) => writeln!(out, "{indent}r{r} = {expr};"),
+                Stmt::If(cond, then, otherwise) => {
+                    writeln!(out, "{indent}if {cond} {{").unwrap();
+                    self.rust(out, then, depth + 1);
+                    if !otherwise.is_empty() {
+                        writeln!(out, "{indent}}} else {{").unwrap();
+                        self.rust(out, otherwise, depth + 1);
+                    }
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::Loop(body) => {
+                    writeln!(out, "{indent}loop {{").unwrap();
+                    self.rust(out, body, depth + 1);
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::While(cond, body) => {
+                    writeln!(out, "{indent}while {cond} {{").unwrap();
+                    self.rust(out, body, depth + 1);
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::DoWhile(body, cond) => {
+                    writeln!(out, "{indent}loop {{").unwrap();
+                    self.rust(out, body, depth + 1);
+                    writeln!(out, "{indent}    if {} {{", cond.negate()).unwrap();
+                    writeln!(out, "{indent}        break;").unwrap();
+                    writeln!(out, "{indent}    }}").unwrap();
+                    writeln!(out, "{indent}}}")
+                }
+                Stmt::Break => writeln!(out, "{indent}break;"),
+                Stmt::Continue => writeln!(out, "{indent}continue;"),
+                Stmt::Goto(target) => {
+                    writeln!(out, "{indent}pc = {target};").unwrap();
+                    writeln!(out, "{indent}continue 'program;")
+                }
+                Stmt::Jump(expr) => {
+                    writeln!(out, "{indent}pc = {};", jump_target(expr)).unwrap();
+                    writeln!(out, "{indent}continue 'program;")
+                }
+                Stmt::Halt(pointer) if !dispatch && depth == 1 && n + 1 == code.len() => {
+                    writeln!(out, "{indent}{}", halt(*pointer))
+                }
+                Stmt::Halt(pointer) => writeln!(out, "{indent}return {};", halt(*pointer)),
+            }
+            .unwrap();
+        }
+    }
+
+    /// Rust function `name` running the program from the given registers and
+    /// returning them when it halts, or the first register the device does
+    /// not have, which the function could not declare.
+    pub fn to_rust(&self, name: &str) -> Result<String, Invalid> {
+        check_registers::<6>(self.ip, self.instructions)?;
+        let written: BTreeSet<_> = self.instructions.iter().map(|instr| instr.c).collect();
+        let variables: Vec<_> = (0..6)
+            .map(|r| match (r == self.ip, written.contains(&r)) {
+                (true, _) => "_".to_string(),
+                (false, true) => format!("mut r{r}"),
+                (false, false) => format!("r{r}"),
+            })
+            .collect();
+
+        let mut out = String::new();
+        writeln!(out, "pub fn {name}(registers: [usize; 6]) -> [usize; 6] {{").unwrap();
+        writeln!(out, "    let [{}] = registers;", variables.join(", ")).unwrap();
+        match &self.sections[..] {
+            [(_, code)] => self.rust(&mut out, code, 1),
+            sections => {
+                writeln!(out, "    let mut pc = 0;").unwrap();
+                writeln!(out, "    'program: loop {{").unwrap();
+                writeln!(out, "        match pc {{").unwrap();
+                for (start, code) in sections {
+                    writeln!(out, "            {start} => {{").unwrap();
+                    self.rust(&mut out, code, 4);
+                    writeln!(out, "            }}").unwrap();
+                }
+                let halt = self.rust_registers("pc - 1");
+                writeln!(out, "            _ => return {halt},").unwrap();
+                writeln!(out, "        }}").unwrap();
+                writeln!(out, "    }}").unwrap();
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        Ok(out)
+    }
+}
+
+/// Sections after the first are labelled with the number of their first
+/// instruction. Falling through to the next section is not shown as a jump.
+impl fmt::Display for Decompiled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labelled = self.sections.len() > 1;
+        for (s, (start, code)) in self.sections.iter().enumerate() {
+            if labelled {
+                writeln!(f, "{start}:")?;
+            }
+            let mut code = &code[..];
+            let next = self.sections.get(s + 1).map(|&(start, _)| start);
+            if let [rest @ .., Stmt::Goto(target)] = code {
+                if Some(*target) == next {
+                    code = rest;
+                }
+            }
+            let mut out = String::new();
+            Self::pseudo_code(&mut out, code, 0);
+            f.write_str(&out)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    // Nested loops, as in day 19:
+    // for r1 in 1..=r5 { for r4 in 1..=r5 { if r1 * r4 == r5 { r0 += r1 } } }
+    const PROGRAM: &str = "\
+#ip 3
+seti 1 0 1
+seti 1 0 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 1 0 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 0 0 3
+mulr 3 3 3
+";
+
+    #[test]
+    fn loops() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let decompiled = Decompiled::new(ip, &instructions);
+        assert_eq!(decompiled.sections.len(), 1);
+        // The last comparison is kept, as r2 is left to the caller
+        assert_eq!(
+            decompiled.to_string(),
+            "\
+r1 = 1
+do {
+    r4 = 1
+    do {
+        r2 = r1 * r4
+        if r2 == r5 {
+            r0 = r1 + r0
+        }
+        r4 = r4 + 1
+    } while r4 <= r5
+    r1 = r1 + 1
+    r2 = r1 > r5
+} while r1 <= r5
+halt
+"
+        );
+    }
+
+    #[test]
+    fn if_else() {
+        let program = "\
+#ip 5
+gtri 1 5 2
+addr 2 5 5
+seti 4 0 5
+addi 0 1 0
+seti 5 0 5
+addi 0 2 0
+seti 0 0 2
+";
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        assert_eq!(
+            Decompiled::new(ip, &instructions).to_string(),
+            "\
+if r1 > 5 {
+    r0 = r0 + 1
+} else {
+    r0 = r0 + 2
+}
+r2 = 0
+halt
+"
+        );
+    }
+
+    #[test]
+    fn rust() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        assert_eq!(
+            Decompiled::new(ip, &instructions)
+                .to_rust("divisors")
+                .unwrap(),
+            "\
+pub fn divisors(registers: [usize; 6]) -> [usize; 6] {
+    let [mut r0, mut r1, mut r2, _, mut r4, r5] = registers;
+    r1 = 1;
+    loop {
+        r4 = 1;
+        loop {
+            r2 = r1 * r4;
+            if r2 == r5 {
+                r0 = r1 + r0;
+            }
+            r4 = r4 + 1;
+            if r4 > r5 {
+                break;
+            }
+        }
+        r1 = r1 + 1;
+        r2 = (r1 > r5) as usize;
+        if r1 > r5 {
+            break;
+        }
+    }
+    [r0, r1, r2, 225, r4, r5]
+}
+"
+        );
+    }
+
+    #[test]
+    fn sections() {
+        // Computed jumps can go to any later instruction
+        let program = "\
+#ip 5
+addr 5 0 5
+seti 1 0 1
+addi 1 7 1
+";
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        let decompiled = Decompiled::new(ip, &instructions);
+        assert_eq!(
+            decompiled.to_string(),
+            "\
+0:
+goto 1 + r0
+1:
+r1 = 1
+2:
+r1 = r1 + 7
+halt
+"
+        );
+        let rust = decompiled.to_rust("program").unwrap();
+        assert!(rust.contains("    'program: loop {\n        match pc {\n"));
+        assert!(rust.contains("                pc = 1 + r0;\n                continue 'program;\n"));
+        assert!(rust.contains("            _ => return [r0, r1, r2, r3, r4, pc - 1],\n"));
+    }
+
+    #[test]
+    fn rust_invalid_register() {
+        let (ip, instructions) = parse_input("#ip 5\naddr 9 0 0".to_string()).unwrap();
+        let err = Decompiled::new(ip, &instructions)
+            .to_rust("program")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "invalid register 9 at instruction 0");
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+
+use super::{parse_ip, Instr, Op, Operand};
+use crate::parse::{self, ParseError};
+
+/// A program as numbered pseudo-code, each line next to its instruction so
+/// that the listing can be read back with [`parse_listing`]:
+///
+/// ```text
+/// #ip 2
+/// 0  seti 123 0 3  r3 = 123
+/// 1  bani 3 456 3  r3 = r3 & 456
+/// 2  eqri 3 72 3   r3 = r3 == 72
+/// 3  addr 3 2 2    if r3 == 72 goto 5
+/// ```
+///
+/// Writes to the register bound to the instruction pointer are shown as
+/// jumps, and reads of it as the number of the instruction.
+pub struct Listing<'a> {
+    pub ip: usize,
+    pub instructions: &'a [Instr],
+}
+
+impl fmt::Display for Listing<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "#ip {}", self.ip)?;
+        let number_width = self.instructions.len().saturating_sub(1).to_string().len();
+        let instr_width = self
+            .instructions
+            .iter()
+            .map(|instr| instr.to_string().len())
+            .max()
+            .unwrap_or(0);
+        for (i, instr) in self.instructions.iter().enumerate() {
+            let instr = instr.to_string();
+            let code = pseudo_code(self.ip, self.instructions, i);
+            writeln!(f, "{i:>number_width$}  {instr:<instr_width$}  {code}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Program of a [`Listing`]. The pseudo-code is ignored, only the numbers
+/// and the instructions are read.
+pub fn parse_listing(input: &str) -> Result<(usize, Vec<Instr>), ParseError> {
+    let mut lines = parse::lines(input);
+    let ip = parse_ip(lines.expect("\"#ip <register>\"")?)?;
+    let mut instructions = Vec::new();
+    for line in lines {
+        let fields: Vec<_> = line.span().split_whitespace().collect();
+        let Some((number, fields)) = fields.split_first().filter(|(_, f)| f.len() >= 4) else {
+            return Err(line.invalid("\"<number> <opcode> <a> <b> <c> <pseudo-code>\""));
+        };
+        let expected = format!("instruction number {}", instructions.len());
+        if line.parse::<usize>(*number, &expected)? != instructions.len() {
+            return Err(line.error(*number, expected));
+        }
+        instructions.push(Instr::from_fields(&line, &fields[..4])?);
+    }
+    Ok((ip, instructions))
+}
+
+/// Operand as seen by the instruction number `i`.
+pub(super) enum Value {
+    Register(usize),
+    Number(usize),
+}
+
+impl Value {
+    fn new(kind: Operand, field: usize, ip: usize, i: usize) -> Option<Self> {
+        match kind {
+            Operand::Register if field == ip => Some(Value::Number(i)),
+            Operand::Register => Some(Value::Register(field)),
+            Operand::Immediate => Some(Value::Number(field)),
+            Operand::Ignored => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Register(r) => write!(f, "r{r}"),
+            Value::Number(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+fn symbol(op: Op) -> Option<&'static str> {
+    match op {
+        Op::Addr | Op::Addi => Some("+"),
+        Op::Mulr | Op::Muli => Some("*"),
+        Op::Banr | Op::Bani => Some("&"),
+        Op::Borr | Op::Bori => Some("|"),
+        Op::Setr | Op::Seti => None,
+        Op::Gtir | Op::Gtri | Op::Gtrr => Some(">"),
+        Op::Eqir | Op::Eqri | Op::Eqrr => Some("=="),
+    }
+}
+
+fn is_comparison(op: Op) -> bool {
+    matches!(symbol(op), Some(">" | "=="))
+}
+
+/// Operands of the instruction number `i`.
+pub(super) fn values(ip: usize, instr: &Instr, i: usize) -> (Value, Option<Value>) {
+    let [a, b] = instr.op.operands();
+    let a = Value::new(a, instr.a, ip, i).unwrap();
+    (a, Value::new(b, instr.b, ip, i))
+}
+
+/// Expression computed by the instruction number `i`.
+fn expression(ip: usize, instr: &Instr, i: usize) -> String {
+    match values(ip, instr, i) {
+        (a, Some(b)) => format!("{a} {} {b}", symbol(instr.op).unwrap()),
+        (a, None) => a.to_string(),
+    }
+}
+
+/// Value of the expression computed by the instruction number `i`, if it
+/// does not depend on registers and does not overflow.
+pub(super) fn constant(ip: usize, instr: &Instr, i: usize) -> Option<usize> {
+    let number = |value| match value {
+        Value::Number(n) => Some(n),
+        Value::Register(_) => None,
+    };
+    let (a, b) = values(ip, instr, i);
+    let mut registers = [0; 6];
+    let a = number(a)?;
+    let b = b.map(number).unwrap_or(Some(0))?;
+    // Run the instruction on registers holding the constant operands
+    let op = instr.op;
+    let [kind_a, kind_b] = op.operands();
+    let mut instr = Instr { op, a, b, c: 0 };
+    if kind_a == Operand::Register {
+        registers[1] = a;
+        instr.a = 1;
+    }
+    if kind_b == Operand::Register {
+        registers[2] = b;
+        instr.b = 2;
+    }
+    instr.apply(&mut registers).ok()?;
+    Some(registers[0])
+}
+
+/// Register added to the instruction pointer by the instruction number `i`,
+/// if it is a relative jump.
+pub(super) fn relative_jump(ip: usize, instr: &Instr, i: usize) -> Option<usize> {
+    match (instr.op, values(ip, instr, i)) {
+        (
+            Op::Addr,
+            (Value::Number(_), Some(Value::Register(r)))
+            | (Value::Register(r), Some(Value::Number(_))),
+        ) if instr.c == ip => Some(r),
+        _ => None,
+    }
+}
+
+/// Number of the comparison deciding the relative jump number `i`: the
+/// previous instruction, if it is a comparison writing the register added to
+/// the instruction pointer. The jump then skips at most one instruction,
+/// assuming it is only reached from the comparison.
+pub(super) fn condition(ip: usize, instructions: &[Instr], i: usize) -> Option<usize> {
+    let r = relative_jump(ip, &instructions[i], i)?;
+    let j = i.checked_sub(1)?;
+    let prev = &instructions[j];
+    (prev.c == r && prev.c != ip && is_comparison(prev.op)).then_some(j)
+}
+
+/// Pseudo-code of the instruction number `i` of a program.
+pub(super) fn pseudo_code(ip: usize, instructions: &[Instr], i: usize) -> String {
+    let instr = &instructions[i];
+    if instr.c != ip {
+        return format!("r{} = {}", instr.c, expression(ip, instr, i));
+    }
+
+    // The instruction pointer is incremented after the instruction
+    let goto = |target: usize| match target < instructions.len() {
+        true => format!("goto {target}"),
+        false => "halt".to_string(),
+    };
+    if let Some(target) = constant(ip, instr, i) {
+        return goto(target.saturating_add(1));
+    }
+    if let Some(j) = condition(ip, instructions, i) {
+        let comparison = expression(ip, &instructions[j], j);
+        return format!("if {comparison} {}", goto(i + 2));
+    }
+    if let Some(r) = relative_jump(ip, instr, i) {
+        return format!("goto {} + r{r}", i + 1);
+    }
+    format!("goto {} + 1", expression(ip, instr, i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::{cfg::Cfg, decompile::Decompiled, parse_input};
+
+    const PROGRAM: &str = "\
+#ip 2
+seti 123 0 3
+bani 3 456 3
+eqri 3 72 3
+addr 3 2 2
+seti 0 0 2
+addi 2 3 2
+mulr 2 2 2
+setr 2 0 4
+gtrr 4 1 5
+addr 5 2 2
+";
+
+    #[test]
+    fn listing() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let listing = Listing {
+            ip,
+            instructions: &instructions,
+        };
+        assert_eq!(
+            listing.to_string(),
+            "\
+#ip 2
+0  seti 123 0 3  r3 = 123
+1  bani 3 456 3  r3 = r3 & 456
+2  eqri 3 72 3   r3 = r3 == 72
+3  addr 3 2 2    if r3 == 72 goto 5
+4  seti 0 0 2    goto 1
+5  addi 2 3 2    goto 9
+6  mulr 2 2 2    halt
+7  setr 2 0 4    r4 = 7
+8  gtrr 4 1 5    r5 = r4 > r1
+9  addr 5 2 2    if r4 > r1 halt
+"
+        );
+    }
+
+    #[test]
+    fn listing_round_trip() {
+        let program = parse_input(PROGRAM.to_string()).unwrap();
+        let listing = Listing {
+            ip: program.0,
+            instructions: &program.1,
+        };
+        assert_eq!(parse_listing(&listing.to_string()).unwrap(), program);
+    }
+
+    #[test]
+    fn listing_out_of_order() {
+        let err = parse_listing("#ip 0\n0 seti 1 0 1  r1 = 1\n2 seti 1 0 1\n").unwrap_err();
+        assert_eq!(err, ParseError::new(3, 1, "2", "instruction number 1"));
+    }
+
+    #[test]
+    fn overflowing_jump() {
+        let program = "#ip 0\nseti 0 0 1\naddi 0 18446744073709551615 0";
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        assert_eq!(constant(ip, &instructions[1], 1), None);
+        let listing = Listing {
+            ip,
+            instructions: &instructions,
+        };
+        assert!(listing
+            .to_string()
+            .ends_with("1  addi 0 18446744073709551615 0  goto 1 + 18446744073709551615 + 1\n"));
+        Cfg::new(ip, &instructions);
+        Decompiled::new(ip, &instructions);
+    }
+}
@@ -0,0 +1,286 @@
+use std::{collections::HashSet, fmt};
+
+use super::{cycle::Visits, optimise::Optimised, Fault, Instr, Machine, Op, Operand};
+
+/// Instruction comparing the input register with another register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Check {
+    /// Number of the instruction
+    pub pc: usize,
+    /// Register compared with the input
+    pub register: usize,
+}
+
+/// Why a program does not have the shape [`checks`] needs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unsupported {
+    /// The input register is bound to the instruction pointer
+    Bound,
+    /// The instruction with this number uses the input register other than
+    /// by comparing it with another register
+    Used(usize),
+    /// No instruction compares the input register with another one
+    NoCheck,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unsupported::Bound => write!(f, "the input is bound to the instruction pointer"),
+            Unsupported::Used(pc) => {
+                write!(
+                    f,
+                    "instruction {pc} uses the input other than to compare it"
+                )
+            }
+            Unsupported::NoCheck => write!(f, "no instruction compares the input"),
+        }
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// The instructions `eqrr` comparing register `input` with another one, if
+/// the program does nothing else with it: then the input only decides
+/// which way these checks go.
+pub fn checks(ip: usize, instructions: &[Instr], input: usize) -> Result<Vec<Check>, Unsupported> {
+    if input == ip {
+        return Err(Unsupported::Bound);
+    }
+    let mut checks = Vec::new();
+    for (pc, instr) in instructions.iter().enumerate() {
+        match (instr.op, instr.a, instr.b) {
+            (Op::Eqrr, a, b) if instr.c != input && (a == input) != (b == input) => {
+                let register = if a == input { b } else { a };
+                checks.push(Check { pc, register });
+            }
+            _ => {
+                let [a, b] = instr.op.operands();
+                if instr.c == input
+                    || a == Operand::Register && instr.a == input
+                    || b == Operand::Register && instr.b == input
+                {
+                    return Err(Unsupported::Used(pc));
+                }
+            }
+        }
+    }
+    match checks.is_empty() {
+        true => Err(Unsupported::NoCheck),
+        false => Ok(checks),
+    }
+}
+
+/// An input value making the program halt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Halting {
+    pub value: usize,
+    /// Number of instructions run before halting
+    pub steps: u64,
+    /// Number of the check equal to the input that leads to halting
+    pub pc: usize,
+}
+
+impl fmt::Display for Halting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} halts after {} instructions, from instruction {}",
+            self.value, self.steps, self.pc
+        )
+    }
+}
+
+/// How the program runs once [`HaltingValues`] has found every value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum End {
+    /// The checks see the same registers again, and the program runs
+    /// forever for any other value
+    Repeats,
+    /// The program halts after this many instructions for any other value
+    Halts(u64),
+}
+
+/// Iterator over the input values making a program halt, see
+/// [`halting_values`].
+pub struct HaltingValues<'p, 'a> {
+    program: &'p Optimised<'a>,
+    machine: Machine<'a, 6>,
+    input: usize,
+    checks: Vec<Check>,
+    /// Values compared with the input so far
+    values: HashSet<usize>,
+    /// Registers at each check so far
+    visits: Visits<6>,
+    end: Option<End>,
+    faulted: bool,
+}
+
+/// Find the [`checks`] of register `input` of `program`, and run it with an
+/// input never equal to the registers compared with it (`usize::MAX`),
+/// yielding the values it is compared with that halt the program when they
+/// are equal, in the order the checks see them, i.e. by number of
+/// instructions run before halting.
+///
+/// A value halts the program if it halts within as many instructions as it
+/// has from the check. Values already compared with, which would have taken
+/// another way before, are skipped. So are values for which the equal check
+/// does not halt, since the program then goes somewhere not run here.
+pub fn halting_values<'p, 'a>(
+    program: &'p Optimised<'a>,
+    input: usize,
+) -> Result<HaltingValues<'p, 'a>, Unsupported> {
+    let mut machine = program.machine();
+    let ip = machine
+        .ip()
+        .expect("Optimised machines bind the instruction pointer");
+    let checks = checks(ip, machine.instructions(), input)?;
+    machine.registers[input] = usize::MAX;
+    Ok(HaltingValues {
+        program,
+        machine,
+        input,
+        checks,
+        values: HashSet::new(),
+        visits: Visits::default(),
+        end: None,
+        faulted: false,
+    })
+}
+
+impl HaltingValues<'_, '_> {
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    /// How the program ends, once every value has been found.
+    pub fn end(&self) -> Option<End> {
+        self.end
+    }
+
+    /// Number of instructions run before the program halts when the input
+    /// is `value`, if it halts soon.
+    fn halts(&self, value: usize) -> Option<u64> {
+        let mut machine = self.machine.clone();
+        machine.registers[self.input] = value;
+        for _ in 0..=machine.instructions().len() {
+            if machine.is_halted() {
+                return Some(machine.steps);
+            }
+            machine.try_step().ok()?;
+        }
+        None
+    }
+}
+
+impl Iterator for HaltingValues<'_, '_> {
+    type Item = Result<Halting, Fault<6>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.end.is_none() && !self.faulted {
+            let pc = self.machine.pointer;
+            let mut found = None;
+            if let Some(check) = self.checks.iter().find(|check| check.pc == pc) {
+                if self.visits.visit(&self.machine).is_some() {
+                    self.end = Some(End::Repeats);
+                    return None;
+                }
+                let value = self.machine.registers_seen()[check.register];
+                if self.values.insert(value) {
+                    found = self.halts(value).map(|steps| Halting { value, steps, pc });
+                }
+            }
+            match self.program.try_step(&mut self.machine) {
+                Ok(true) => {}
+                Ok(false) => self.end = Some(End::Halts(self.machine.steps)),
+                Err(fault) => {
+                    self.faulted = true;
+                    return Some(Err(fault));
+                }
+            }
+            if let Some(halting) = found {
+                return Some(Ok(halting));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    // r0 is compared with 3 * r1 % 8 for r1 in 1, 2, ... 7, 0, 1, ... and
+    // with r1 when r1 is 5, halting if they are equal
+    const PROGRAM: &str = "\
+#ip 4
+seti 0 0 1
+addi 1 1 1
+bani 1 7 1
+muli 1 3 3
+bani 3 7 3
+eqrr 3 0 2
+addr 2 4 4
+addi 4 1 4
+seti 99 0 4
+eqri 1 5 2
+addr 2 4 4
+seti 0 0 4
+eqrr 0 1 2
+addr 2 4 4
+seti 0 0 4
+";
+
+    #[test]
+    fn values_in_order() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let program = Optimised::new(ip, &instructions);
+        let mut values = halting_values(&program, 0).unwrap();
+        assert_eq!(
+            values.checks(),
+            [
+                Check { pc: 5, register: 3 },
+                Check {
+                    pc: 12,
+                    register: 1
+                }
+            ]
+        );
+        let found: Vec<_> = values.by_ref().map(|halting| halting.unwrap()).collect();
+        // 5 is compared at instruction 12 before instruction 5, where it
+        // is skipped
+        let summary: Vec<_> = found.iter().map(|h| (h.value, h.pc)).collect();
+        assert_eq!(
+            summary,
+            [
+                (3, 5),
+                (6, 5),
+                (1, 5),
+                (4, 5),
+                (7, 5),
+                (5, 12),
+                (2, 5),
+                (0, 5)
+            ]
+        );
+        assert_eq!(found[0].steps, 8);
+        assert!(found.windows(2).all(|w| w[0].steps < w[1].steps));
+        assert_eq!(values.end(), Some(End::Repeats));
+
+        let mut machine = program.machine();
+        machine.registers[0] = 4;
+        assert_eq!(program.run(&mut machine), found[3].steps);
+    }
+
+    #[test]
+    fn unsupported() {
+        let (ip, mut instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        assert_eq!(checks(ip, &instructions, 4), Err(Unsupported::Bound));
+        assert_eq!(checks(ip, &instructions, 5), Err(Unsupported::NoCheck));
+        assert_eq!(checks(ip, &instructions, 3), Err(Unsupported::Used(3)));
+        instructions[2].b = 0;
+        instructions[2].op = Op::Banr;
+        assert_eq!(checks(ip, &instructions, 0), Err(Unsupported::Used(2)));
+    }
+}
@@ -0,0 +1,366 @@
+use std::{collections::BTreeMap, fmt};
+
+use super::{Arithmetic, Instr, Op};
+
+/// An instruction whose opcode number is known but not its operation, and
+/// the registers before and after it ran.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample<const N: usize> {
+    pub before: [usize; N],
+    pub opcode: usize,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    pub after: [usize; N],
+}
+
+impl<const N: usize> Sample<N> {
+    /// Whether running the instruction as `op` gives the registers after it.
+    /// Instructions `op` cannot run (e.g. with a register out of range)
+    /// never match.
+    pub fn matches(&self, op: Op) -> bool {
+        let instr = Instr {
+            op,
+            a: self.a,
+            b: self.b,
+            c: self.c,
+        };
+        let mut registers = self.before;
+        instr.execute(&mut registers, Arithmetic::Checked).is_ok() && registers == self.after
+    }
+
+    /// The operations of `ops` matching the sample.
+    pub fn matching(&self, ops: &[Op]) -> Vec<Op> {
+        ops.iter().copied().filter(|&op| self.matches(op)).collect()
+    }
+}
+
+/// Why an opcode is the operation it was identified as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// No other operation matches all the samples of the opcode, from the
+    /// sample with this index on
+    Samples(usize),
+    /// The other operations matching its samples are these opcodes
+    Elimination(Vec<(Op, usize)>),
+    /// No other opcode can be the operation, and each operation is the
+    /// operation of an opcode
+    OnlyOpcode,
+}
+
+/// An opcode identified as an operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deduction {
+    pub opcode: usize,
+    pub op: Op,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Deduction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "opcode {} is {}: ", self.opcode, self.op)?;
+        match &self.reason {
+            Reason::Samples(sample) => {
+                write!(
+                    f,
+                    "the only operation matching its samples from sample {sample} on"
+                )
+            }
+            Reason::Elimination(others) => {
+                write!(f, "the other operations matching its samples are")?;
+                for (i, (op, opcode)) in others.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { "," };
+                    write!(f, "{separator} {op} (opcode {opcode})")?;
+                }
+                Ok(())
+            }
+            Reason::OnlyOpcode => write!(f, "no other opcode can be {}", self.op),
+        }
+    }
+}
+
+/// Samples inconsistent with the operations, or with each other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// No operation matches the sample with this index
+    NoMatch(usize),
+    /// The sample with this index matches none of the operations left for
+    /// its opcode by the samples before it, and is ignored
+    Contradiction(usize),
+    /// The samples of these opcodes only match the same operation
+    Conflict(Vec<usize>, Op),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::NoMatch(sample) => write!(f, "sample {sample} matches no operation"),
+            Problem::Contradiction(sample) => write!(
+                f,
+                "sample {sample} contradicts the samples of its opcode before it"
+            ),
+            Problem::Conflict(opcodes, op) => {
+                let opcodes: Vec<_> = opcodes.iter().map(usize::to_string).collect();
+                write!(f, "opcodes {} can only be {op}", opcodes.join(", "))
+            }
+        }
+    }
+}
+
+/// What samples tell about the operations of the opcodes they use, see
+/// [`identify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identification {
+    /// Operations matching each sample
+    pub matches: Vec<Vec<Op>>,
+    /// Operations left for each opcode, in the order of the instruction set
+    pub candidates: BTreeMap<usize, Vec<Op>>,
+    /// Opcodes identified, in the order they were
+    pub deductions: Vec<Deduction>,
+    pub problems: Vec<Problem>,
+}
+
+impl Identification {
+    /// Indices of the samples matching several operations.
+    pub fn ambiguous(&self) -> impl Iterator<Item = usize> + '_ {
+        self.matches
+            .iter()
+            .enumerate()
+            .filter(|(_, ops)| ops.len() > 1)
+            .map(|(i, _)| i)
+    }
+
+    /// Operation of `opcode`, if it was identified.
+    pub fn op(&self, opcode: usize) -> Option<Op> {
+        self.deductions
+            .iter()
+            .find(|deduction| deduction.opcode == opcode)
+            .map(|deduction| deduction.op)
+    }
+
+    /// Operation of every opcode sampled, if they were all identified.
+    pub fn mapping(&self) -> Option<BTreeMap<usize, Op>> {
+        self.candidates
+            .keys()
+            .map(|&opcode| Some((opcode, self.op(opcode)?)))
+            .collect()
+    }
+}
+
+impl fmt::Display for Identification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for deduction in &self.deductions {
+            writeln!(f, "{deduction}")?;
+        }
+        for (opcode, ops) in &self.candidates {
+            // A single operation left is in a conflict
+            if ops.len() > 1 {
+                let ops: Vec<_> = ops.iter().map(|op| op.name()).collect();
+                writeln!(f, "opcode {opcode} is one of {}", ops.join(", "))?;
+            }
+        }
+        for problem in &self.problems {
+            writeln!(f, "{problem}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Identify the operations of the opcodes used by `samples`, among the
+/// instruction set `ops`, assuming different opcodes are different
+/// operations.
+///
+/// Each opcode can be any operation matching all its samples; samples
+/// leaving none are reported and ignored. Opcodes left with one operation
+/// are identified, ruling it out for the others, until no more are. If
+/// there are as many opcodes as operations, an operation only one opcode
+/// can be also identifies it.
+pub fn identify<const N: usize>(ops: &[Op], samples: &[Sample<N>]) -> Identification {
+    let mut identification = Identification {
+        matches: samples.iter().map(|sample| sample.matching(ops)).collect(),
+        candidates: BTreeMap::new(),
+        deductions: Vec::new(),
+        problems: Vec::new(),
+    };
+    let candidates = &mut identification.candidates;
+    let problems = &mut identification.problems;
+
+    // Sample from which each opcode had a single candidate
+    let mut narrowed = BTreeMap::new();
+    for (i, (sample, matches)) in samples.iter().zip(&identification.matches).enumerate() {
+        let left = candidates
+            .entry(sample.opcode)
+            .or_insert_with(|| ops.to_vec());
+        if matches.is_empty() {
+            problems.push(Problem::NoMatch(i));
+            continue;
+        }
+        if !left.iter().any(|op| matches.contains(op)) {
+            problems.push(Problem::Contradiction(i));
+            continue;
+        }
+        left.retain(|op| matches.contains(op));
+        if left.len() == 1 {
+            narrowed.entry(sample.opcode).or_insert(i);
+        }
+    }
+
+    // Operations ruled out for each opcode, with the opcode they are
+    let mut eliminated: BTreeMap<usize, Vec<(Op, usize)>> = BTreeMap::new();
+    let mut conflicts: Vec<(Op, Vec<usize>)> = Vec::new();
+    let mut done = Vec::new();
+    loop {
+        let single = candidates
+            .iter()
+            .find(|(opcode, left)| left.len() == 1 && !done.contains(*opcode))
+            .map(|(&opcode, left)| (opcode, left[0]));
+        if let Some((opcode, op)) = single {
+            done.push(opcode);
+            let reason = match eliminated.remove(&opcode) {
+                Some(others) => Reason::Elimination(others),
+                None => match narrowed.get(&opcode) {
+                    Some(&sample) => Reason::Samples(sample),
+                    None => Reason::OnlyOpcode,
+                },
+            };
+            identification
+                .deductions
+                .push(Deduction { opcode, op, reason });
+            for (&other, left) in candidates.iter_mut() {
+                if other == opcode || !left.contains(&op) {
+                    continue;
+                }
+                if left.len() == 1 {
+                    done.push(other);
+                    match conflicts.iter_mut().find(|(o, _)| *o == op) {
+                        Some((_, opcodes)) => opcodes.push(other),
+                        None => conflicts.push((op, vec![opcode, other])),
+                    }
+                    continue;
+                }
+                left.retain(|&o| o != op);
+                eliminated.entry(other).or_default().push((op, opcode));
+            }
+            continue;
+        }
+
+        // Opcodes conflicting over an operation break the assumption
+        if candidates.len() != ops.len() || !conflicts.is_empty() {
+            break;
+        }
+        let hidden = ops.iter().find_map(|&op| {
+            let mut opcodes = candidates
+                .iter()
+                .filter(|(_, left)| left.contains(&op))
+                .map(|(&opcode, _)| opcode);
+            match (opcodes.next(), opcodes.next()) {
+                (Some(opcode), None) if candidates[&opcode].len() > 1 => Some((opcode, op)),
+                _ => None,
+            }
+        });
+        let Some((opcode, op)) = hidden else {
+            break;
+        };
+        candidates.insert(opcode, vec![op]);
+        eliminated.remove(&opcode);
+        narrowed.remove(&opcode);
+    }
+    problems.extend(
+        conflicts
+            .into_iter()
+            .map(|(op, opcodes)| Problem::Conflict(opcodes, op)),
+    );
+    identification
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(before: [usize; 4], [opcode, a, b, c]: [usize; 4], after: [usize; 4]) -> Sample<4> {
+        Sample {
+            before,
+            opcode,
+            a,
+            b,
+            c,
+            after,
+        }
+    }
+
+    #[test]
+    fn matching() {
+        let example = sample([3, 2, 1, 1], [9, 2, 1, 2], [3, 2, 2, 1]);
+        assert_eq!(example.matching(&Op::ALL), [Op::Addi, Op::Mulr, Op::Seti]);
+        // Register 7 does not exist
+        let example = sample([3, 2, 1, 1], [9, 7, 1, 2], [3, 2, 7, 1]);
+        assert_eq!(example.matching(&Op::ALL), [Op::Seti]);
+    }
+
+    #[test]
+    fn deductions() {
+        let ops = [Op::Addi, Op::Mulr, Op::Seti];
+        let samples = [
+            // addi or seti
+            sample([0, 0, 2, 0], [5, 2, 0, 1], [0, 2, 2, 0]),
+            // mulr
+            sample([0, 2, 3, 0], [7, 1, 2, 3], [0, 2, 3, 6]),
+            // addi, mulr or seti
+            sample([3, 2, 1, 1], [1, 2, 1, 2], [3, 2, 2, 1]),
+            // matches nothing
+            sample([0, 0, 0, 0], [7, 0, 0, 0], [1, 1, 1, 1]),
+            // addi, not mulr
+            sample([0, 2, 3, 0], [1, 1, 2, 3], [0, 2, 3, 4]),
+        ];
+        let identification = identify(&ops, &samples);
+        assert_eq!(identification.ambiguous().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(identification.problems, [Problem::NoMatch(3)]);
+        assert_eq!(
+            identification.mapping(),
+            Some(BTreeMap::from([
+                (1, Op::Addi),
+                (5, Op::Seti),
+                (7, Op::Mulr)
+            ]))
+        );
+        assert_eq!(
+            identification.to_string(),
+            "opcode 1 is addi: the only operation matching its samples from sample 4 on\n\
+             opcode 5 is seti: the other operations matching its samples are addi (opcode 1)\n\
+             opcode 7 is mulr: the only operation matching its samples from sample 1 on\n\
+             sample 3 matches no operation\n"
+        );
+    }
+
+    #[test]
+    fn problems() {
+        let ops = [Op::Addi, Op::Mulr, Op::Seti];
+        let samples = [
+            // mulr
+            sample([0, 2, 3, 0], [7, 1, 2, 3], [0, 2, 3, 6]),
+            // addi, contradicting the sample before
+            sample([0, 2, 3, 0], [7, 1, 2, 3], [0, 2, 3, 4]),
+            // mulr too
+            sample([0, 2, 3, 0], [4, 1, 2, 3], [0, 2, 3, 6]),
+            // addi or seti
+            sample([0, 0, 2, 0], [5, 2, 0, 1], [0, 2, 2, 0]),
+        ];
+        let identification = identify(&ops, &samples);
+        assert_eq!(identification.op(5), None);
+        assert_eq!(identification.mapping(), None);
+        assert_eq!(
+            identification.problems,
+            [
+                Problem::Contradiction(1),
+                Problem::Conflict(vec![4, 7], Op::Mulr)
+            ]
+        );
+        assert_eq!(
+            identification.to_string(),
+            "opcode 4 is mulr: the only operation matching its samples from sample 2 on\n\
+             opcode 5 is one of addi, seti\n\
+             sample 1 contradicts the samples of its opcode before it\n\
+             opcodes 4, 7 can only be mulr\n"
+        );
+    }
+}
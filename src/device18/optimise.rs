@@ -0,0 +1,551 @@
+use std::ops::Range;
+
+use super::{Fault, Instr, Machine, Op};
+
+/// Loop of a program computing something simple the slow way, run by the
+/// [`Optimised`] interpreter in one step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Idiom {
+    /// `quotient = max(quotient, dividend / divisor)`, by increasing the
+    /// quotient until `(quotient + 1) * divisor > dividend`:
+    ///
+    /// ```text
+    /// scratch = quotient + 1
+    /// scratch = scratch * divisor
+    /// scratch = scratch > dividend
+    /// if scratch > dividend goto exit
+    /// quotient = quotient + 1
+    /// goto start
+    /// ```
+    Divide {
+        quotient: usize,
+        scratch: usize,
+        dividend: usize,
+        divisor: usize,
+        exit: usize,
+    },
+    /// `sum += x` for each `x` from `x` to `n` dividing `n`, by trying every
+    /// product `x * y` for `y` from 1 to `n`:
+    ///
+    /// ```text
+    /// y = 1
+    /// product = x * y
+    /// if product == n: sum = x + sum
+    /// y = y + 1
+    /// if y <= n goto start + 1
+    /// x = x + 1
+    /// if x <= n goto start
+    /// ```
+    DivisorSum {
+        x: usize,
+        y: usize,
+        product: usize,
+        n: usize,
+        sum: usize,
+        exit: usize,
+    },
+}
+
+/// An [`Idiom`] found in a program, replacing its instructions `range`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Superinstruction {
+    pub range: Range<usize>,
+    pub idiom: Idiom,
+}
+
+/// Field of an instruction in a pattern.
+#[derive(Clone, Copy)]
+enum Field {
+    /// Register, the same one for each occurrence of the variable, distinct
+    /// from the other variables and from the instruction pointer
+    Reg(usize),
+    /// Register bound to the instruction pointer
+    Ip,
+    /// Any number, the same one for each occurrence of the variable
+    Const(usize),
+    /// This number
+    Lit(usize),
+    /// The number of the first instruction plus this offset
+    Rel(isize),
+    Any,
+}
+
+use Field::*;
+
+type Pattern = &'static [(Op, Field, Field, Field)];
+
+const DIVIDE: Pattern = &[
+    (Op::Addi, Reg(0), Lit(1), Reg(1)),
+    (Op::Muli, Reg(1), Const(0), Reg(1)),
+    (Op::Gtrr, Reg(1), Reg(2), Reg(1)),
+    (Op::Addr, Reg(1), Ip, Ip),
+    (Op::Addi, Ip, Lit(1), Ip),
+    (Op::Seti, Const(1), Any, Ip),
+    (Op::Addi, Reg(0), Lit(1), Reg(0)),
+    (Op::Seti, Rel(-1), Any, Ip),
+];
+
+const DIVISOR_SUM: Pattern = &[
+    (Op::Seti, Lit(1), Any, Reg(1)),
+    (Op::Mulr, Reg(0), Reg(1), Reg(2)),
+    (Op::Eqrr, Reg(2), Reg(3), Reg(2)),
+    (Op::Addr, Reg(2), Ip, Ip),
+    (Op::Addi, Ip, Lit(1), Ip),
+    (Op::Addr, Reg(0), Reg(4), Reg(4)),
+    (Op::Addi, Reg(1), Lit(1), Reg(1)),
+    (Op::Gtrr, Reg(1), Reg(3), Reg(2)),
+    (Op::Addr, Reg(2), Ip, Ip),
+    (Op::Seti, Rel(0), Any, Ip),
+    (Op::Addi, Reg(0), Lit(1), Reg(0)),
+    (Op::Gtrr, Reg(0), Reg(3), Reg(2)),
+    (Op::Addr, Reg(2), Ip, Ip),
+    (Op::Seti, Rel(-1), Any, Ip),
+];
+
+/// Registers and numbers bound to the variables of a pattern.
+#[derive(Clone, Default)]
+struct Bindings {
+    registers: [Option<usize>; 6],
+    constants: [Option<usize>; 2],
+}
+
+impl Bindings {
+    fn bind(&mut self, field: Field, value: usize, ip: usize, start: usize) -> bool {
+        match field {
+            Reg(v) => {
+                if value == ip {
+                    return false;
+                }
+                match self.registers[v] {
+                    Some(r) => r == value,
+                    None if self.registers.contains(&Some(value)) => false,
+                    None => {
+                        self.registers[v] = Some(value);
+                        true
+                    }
+                }
+            }
+            Ip => value == ip,
+            Const(v) => *self.constants[v].get_or_insert(value) == value,
+            Lit(n) => value == n,
+            Rel(offset) => start.checked_add_signed(offset) == Some(value),
+            Any => true,
+        }
+    }
+
+    fn matches(
+        &mut self,
+        pattern: (Op, Field, Field, Field),
+        instr: &Instr,
+        ip: usize,
+        start: usize,
+    ) -> bool {
+        let (op, a, b, c) = pattern;
+        if instr.op != op {
+            return false;
+        }
+        let saved = self.clone();
+        if self.bind(a, instr.a, ip, start)
+            && self.bind(b, instr.b, ip, start)
+            && self.bind(c, instr.c, ip, start)
+        {
+            return true;
+        }
+        *self = saved;
+        let commutative = matches!(op, Op::Addr | Op::Mulr | Op::Banr | Op::Borr | Op::Eqrr);
+        commutative
+            && self.bind(a, instr.b, ip, start)
+            && self.bind(b, instr.a, ip, start)
+            && self.bind(c, instr.c, ip, start)
+    }
+}
+
+fn find(pattern: Pattern, ip: usize, instructions: &[Instr], start: usize) -> Option<Bindings> {
+    let code = instructions.get(start..start + pattern.len())?;
+    let mut bindings = Bindings::default();
+    code.iter()
+        .zip(pattern)
+        .all(|(instr, &p)| bindings.matches(p, instr, ip, start))
+        .then_some(bindings)
+}
+
+impl Idiom {
+    /// Idioms starting at the instruction number `start` of a program.
+    fn find(ip: usize, instructions: &[Instr], start: usize) -> Option<Superinstruction> {
+        let r = |bindings: &Bindings, v: usize| bindings.registers[v].unwrap();
+        if let Some(b) = find(DIVIDE, ip, instructions, start) {
+            let idiom = Idiom::Divide {
+                quotient: r(&b, 0),
+                scratch: r(&b, 1),
+                dividend: r(&b, 2),
+                divisor: b.constants[0].unwrap(),
+                exit: b.constants[1].unwrap().checked_add(1)?,
+            };
+            return Some(Superinstruction {
+                range: start..start + DIVIDE.len(),
+                idiom,
+            });
+        }
+        if let Some(b) = find(DIVISOR_SUM, ip, instructions, start) {
+            let idiom = Idiom::DivisorSum {
+                x: r(&b, 0),
+                y: r(&b, 1),
+                product: r(&b, 2),
+                n: r(&b, 3),
+                sum: r(&b, 4),
+                exit: start + DIVISOR_SUM.len(),
+            };
+            return Some(Superinstruction {
+                range: start..start + DIVISOR_SUM.len(),
+                idiom,
+            });
+        }
+        None
+    }
+
+    /// Run the idiom, leaving the instruction pointer register just before
+    /// its exit, and return the number of instructions of the loop it
+    /// replaces. `None` (and no change) if it only handles the usual cases
+    /// and the registers are not one of them.
+    pub fn apply(&self, ip: usize, registers: &mut [usize; 6]) -> Option<u64> {
+        match *self {
+            Idiom::Divide {
+                quotient,
+                scratch,
+                dividend,
+                divisor,
+                exit,
+            } => {
+                let (q, n) = (registers[quotient], registers[dividend]);
+                if divisor == 0 || n.checked_add(divisor).is_none() {
+                    return None;
+                }
+                q.checked_add(1)?.checked_mul(divisor)?;
+                let result = q.max(n / divisor);
+                // 7 instructions per increment, 5 for the last test
+                let steps = 7 * (result - q) as u64 + 5;
+                registers[quotient] = result;
+                registers[scratch] = 1;
+                registers[ip] = exit - 1;
+                Some(steps)
+            }
+            Idiom::DivisorSum {
+                x,
+                y,
+                product,
+                n,
+                sum,
+                exit,
+            } => {
+                let (first, n_value) = (registers[x], registers[n]);
+                if first == 0 || first > n_value || n_value.checked_mul(n_value).is_none() {
+                    return None;
+                }
+                let mut total = registers[sum];
+                let mut d = 1;
+                while d * d <= n_value {
+                    if n_value % d == 0 {
+                        let pair = n_value / d;
+                        if d >= first {
+                            total = total.checked_add(d)?;
+                        }
+                        if pair != d && pair >= first {
+                            total = total.checked_add(pair)?;
+                        }
+                    }
+                    d += 1;
+                }
+                // Per value of x: y = 1, 8 per value of y but the last
+                // taking 7, then 3 to increment and test x, and 1 to loop
+                // but for the last value of x
+                let (count, n_steps) = ((n_value - first + 1) as u64, n_value as u64);
+                let steps = count * (8 * n_steps + 3) + count - 1;
+                registers[sum] = total;
+                registers[x] = n_value + 1;
+                registers[y] = n_value + 1;
+                registers[product] = 1;
+                registers[ip] = exit - 1;
+                Some(steps)
+            }
+        }
+    }
+}
+
+/// Interpreter running the [`Idiom`]s of a program in one step each, when
+/// they behave like the plain instructions on every sample tried.
+pub struct Optimised<'a> {
+    ip: usize,
+    instructions: &'a [Instr],
+    superinstructions: Vec<Option<Idiom>>,
+}
+
+impl<'a> Optimised<'a> {
+    pub fn new(ip: usize, instructions: &'a [Instr]) -> Self {
+        let superinstructions = (0..instructions.len())
+            .map(|start| {
+                let found = Idiom::find(ip, instructions, start)?;
+                verify(ip, instructions, &found).then_some(found.idiom)
+            })
+            .collect();
+        Optimised {
+            ip,
+            instructions,
+            superinstructions,
+        }
+    }
+
+    /// The idioms used, in program order.
+    pub fn superinstructions(&self) -> impl Iterator<Item = (usize, &Idiom)> {
+        self.superinstructions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, idiom)| Some((i, idiom.as_ref()?)))
+    }
+
+    /// Machine about to run the program, with cleared registers.
+    pub fn machine(&self) -> Machine<'a, 6> {
+        Machine::new(Some(self.ip), self.instructions)
+    }
+
+    /// Run the next instruction of `machine`, a machine running the same
+    /// program, or the idiom starting there, counting the plain instructions
+    /// it stands for. Returns false if the program halted instead.
+    pub fn try_step(&self, machine: &mut Machine<'_, 6>) -> Result<bool, Fault<6>> {
+        let Some(idiom) = self.superinstructions.get(machine.pointer) else {
+            return Ok(false);
+        };
+        let registers = machine.registers;
+        machine.registers[self.ip] = machine.pointer;
+        match idiom.and_then(|idiom| idiom.apply(self.ip, &mut machine.registers)) {
+            Some(steps) => {
+                machine.pointer = machine.registers[self.ip] + 1;
+                machine.steps += steps;
+                Ok(true)
+            }
+            None => {
+                machine.registers = registers;
+                machine.try_step()
+            }
+        }
+    }
+
+    pub fn step(&self, machine: &mut Machine<'_, 6>) -> bool {
+        self.try_step(machine)
+            .unwrap_or_else(|fault| panic!("{fault}"))
+    }
+
+    /// Run the program on `machine` until it halts, and return the number of
+    /// plain instructions run.
+    pub fn run(&self, machine: &mut Machine<'_, 6>) -> u64 {
+        while self.step(machine) {}
+        machine.steps
+    }
+}
+
+/// Run the plain instructions of a program from the instruction `start`,
+/// until leaving `range` or after `limit` instructions. Returns the number
+/// of instructions run, or `None` at the limit.
+fn interpret(
+    ip: usize,
+    instructions: &[Instr],
+    range: Range<usize>,
+    start: usize,
+    registers: &mut [usize; 6],
+    limit: u64,
+) -> Option<u64> {
+    let mut machine = Machine::new(Some(ip), instructions);
+    machine.registers = *registers;
+    machine.pointer = start;
+    let limited = machine.run_until(|m| !range.contains(&m.pointer) || m.steps == limit);
+    *registers = machine.registers;
+    match limited && range.contains(&machine.pointer) && !machine.is_halted() {
+        true => None,
+        false => Some(machine.steps),
+    }
+}
+
+/// Whether a superinstruction leaves the same registers after the same
+/// number of instructions as the plain instructions it replaces, on
+/// pseudo-random samples of small registers. Samples for which the plain
+/// instructions take too long are skipped.
+fn verify(ip: usize, instructions: &[Instr], superinstruction: &Superinstruction) -> bool {
+    let start = superinstruction.range.start;
+    let mut seed: u64 = 0x2018_1921;
+    let mut random = |bound: u64| {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound) as usize
+    };
+
+    let mut compared = 0;
+    for _ in 0..200 {
+        let mut registers = [0; 6];
+        for register in &mut registers {
+            *register = match random(8) {
+                0 => random(1024),
+                _ => random(32),
+            };
+        }
+        registers[ip] = start;
+        let mut expected = registers;
+        let Some(steps) = superinstruction.idiom.apply(ip, &mut registers) else {
+            continue;
+        };
+        let range = superinstruction.range.clone();
+        // Too slow to compare
+        let Some(expected_steps) = interpret(ip, instructions, range, start, &mut expected, 20_000)
+        else {
+            continue;
+        };
+        if registers != expected || steps != expected_steps {
+            return false;
+        }
+        compared += 1;
+    }
+    compared > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    // for r1 in 1..=r5 { for r4 in 1..=r5 { if r1 * r4 == r5 { r0 += r1 } } }
+    const DIVISOR_SUM_PROGRAM: &str = "\
+#ip 3
+seti 1 0 1
+seti 1 0 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 1 0 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 0 0 3
+mulr 3 3 3
+";
+
+    // r3 = r3 / 256 until r3 == 0, r5 counting the divisions
+    const DIVIDE_PROGRAM: &str = "\
+#ip 2
+seti 0 0 1
+addi 1 1 4
+muli 4 256 4
+gtrr 4 3 4
+addr 4 2 2
+addi 2 1 2
+seti 8 0 2
+addi 1 1 1
+seti 0 0 2
+setr 1 0 3
+addi 5 1 5
+gtri 3 0 4
+addr 4 2 2
+seti 99 0 2
+seti 0 0 1
+seti 0 0 2
+";
+
+    /// Registers and number of instructions after running a whole program
+    /// with the plain interpreter and with the optimised one.
+    fn both(program: &str, registers: [usize; 6]) -> [([usize; 6], u64); 2] {
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        let mut plain = Machine::new(Some(ip), &instructions);
+        plain.registers = registers;
+        plain.run();
+        let program = Optimised::new(ip, &instructions);
+        let mut optimised = program.machine();
+        optimised.registers = registers;
+        program.run(&mut optimised);
+        [
+            (plain.registers, plain.steps),
+            (optimised.registers, optimised.steps),
+        ]
+    }
+
+    #[test]
+    fn finds_divisor_sum() {
+        let (ip, instructions) = parse_input(DIVISOR_SUM_PROGRAM.to_string()).unwrap();
+        let optimised = Optimised::new(ip, &instructions);
+        let idioms: Vec<_> = optimised.superinstructions().collect();
+        assert_eq!(
+            idioms,
+            [(
+                1,
+                &Idiom::DivisorSum {
+                    x: 1,
+                    y: 4,
+                    product: 2,
+                    n: 5,
+                    sum: 0,
+                    exit: 15
+                }
+            )]
+        );
+        for n in [1, 12, 97, 360] {
+            let [plain, optimised] = both(DIVISOR_SUM_PROGRAM, [0, 0, 0, 0, 0, n]);
+            assert_eq!(plain, optimised);
+        }
+        let mut machine = optimised.machine();
+        machine.registers[5] = 10551236;
+        optimised.run(&mut machine);
+        // 2^2 * 389 * 6781
+        assert_eq!(machine.registers[0], 7 * 390 * 6782);
+    }
+
+    #[test]
+    fn finds_divide() {
+        let (ip, instructions) = parse_input(DIVIDE_PROGRAM.to_string()).unwrap();
+        let optimised = Optimised::new(ip, &instructions);
+        let idioms: Vec<_> = optimised.superinstructions().collect();
+        assert_eq!(
+            idioms,
+            [(
+                1,
+                &Idiom::Divide {
+                    quotient: 1,
+                    scratch: 4,
+                    dividend: 3,
+                    divisor: 256,
+                    exit: 9
+                }
+            )]
+        );
+        for n in [0, 255, 256, 65536, 16777215] {
+            let [plain, optimised] = both(DIVIDE_PROGRAM, [0, 0, 0, n, 0, 0]);
+            assert_eq!(plain, optimised);
+        }
+        // Left to the interpreter when the next quotient overflows
+        let mut registers = [0, usize::MAX, 0, 256, 0, 0];
+        assert_eq!(idioms[0].1.apply(ip, &mut registers), None);
+        assert_eq!(registers, [0, usize::MAX, 0, 256, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_wrong_idiom() {
+        let (ip, instructions) = parse_input(DIVISOR_SUM_PROGRAM.to_string()).unwrap();
+        let mut found = Idiom::find(ip, &instructions, 1).unwrap();
+        assert!(verify(ip, &instructions, &found));
+        if let Idiom::DivisorSum { ref mut sum, .. } = found.idiom {
+            *sum = 2;
+        }
+        assert!(!verify(ip, &instructions, &found));
+        // Not the same loop: the sum is only increased by 1
+        let changed = DIVISOR_SUM_PROGRAM.replace("addr 1 0 0", "addi 0 1 0");
+        let (ip, instructions) = parse_input(changed).unwrap();
+        assert_eq!(
+            Optimised::new(ip, &instructions)
+                .superinstructions()
+                .count(),
+            0
+        );
+    }
+}
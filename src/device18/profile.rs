@@ -0,0 +1,204 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use super::{disasm::pseudo_code, Fault, Instr, Machine};
+
+/// Number of times each value was seen.
+pub type Histogram = HashMap<usize, u64>;
+
+/// Measurements of one run of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile {
+    /// Number of times each instruction ran
+    pub counts: Vec<u64>,
+    /// Number of instructions run
+    pub steps: u64,
+    /// Whether the program halted, rather than reaching the step limit
+    pub halted: bool,
+    /// Values of each register before running the chosen instructions
+    pub histograms: BTreeMap<usize, [Histogram; 6]>,
+}
+
+/// Run a program from `registers`, until it halts or after `limit`
+/// instructions, counting the instructions run and the values of the
+/// registers before each instruction numbered in `at`. Stops at the first
+/// fault.
+pub fn profile(
+    ip: usize,
+    instructions: &[Instr],
+    registers: [usize; 6],
+    at: &[usize],
+    limit: Option<u64>,
+) -> Result<Profile, Fault<6>> {
+    let mut profile = Profile {
+        counts: vec![0; instructions.len()],
+        steps: 0,
+        halted: false,
+        histograms: at.iter().map(|&i| (i, Default::default())).collect(),
+    };
+    let mut machine = Machine::new(Some(ip), instructions);
+    machine.registers = registers;
+    machine.pointer = registers[ip];
+    while !machine.is_halted() {
+        if limit == Some(machine.steps) {
+            break;
+        }
+        let pointer = machine.pointer;
+        if let Some(histograms) = profile.histograms.get_mut(&pointer) {
+            // As seen by the instruction
+            machine.registers[ip] = pointer;
+            for (histogram, &value) in histograms.iter_mut().zip(&machine.registers) {
+                *histogram.entry(value).or_default() += 1;
+            }
+        }
+        machine.try_step()?;
+        profile.counts[pointer] += 1;
+    }
+    profile.steps = machine.steps;
+    profile.halted = machine.is_halted();
+    Ok(profile)
+}
+
+/// Hot-spot report of a [`Profile`]: the `top` instructions run the most,
+/// with their share of the run, then the most frequent values of the
+/// registers at the chosen instructions.
+pub struct Report<'a> {
+    pub ip: usize,
+    pub instructions: &'a [Instr],
+    pub profile: &'a Profile,
+    pub top: usize,
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let profile = self.profile;
+        let end = match profile.halted {
+            true => "halted",
+            false => "stopped at the step limit",
+        };
+        writeln!(f, "{} instructions run, {end}", profile.steps)?;
+
+        let mut hot: Vec<_> = (0..profile.counts.len())
+            .filter(|&i| profile.counts[i] > 0)
+            .collect();
+        hot.sort_by_key(|&i| (std::cmp::Reverse(profile.counts[i]), i));
+        hot.truncate(self.top);
+        let count_width = hot
+            .first()
+            .map_or(0, |&i| profile.counts[i].to_string().len());
+        let number_width = self.instructions.len().saturating_sub(1).to_string().len();
+        writeln!(f, "\nHot spots:")?;
+        for i in hot {
+            let count = profile.counts[i];
+            let share = 100.0 * count as f64 / profile.steps as f64;
+            let instr = self.instructions[i].to_string();
+            let code = pseudo_code(self.ip, self.instructions, i);
+            writeln!(
+                f,
+                "{count:>count_width$} {share:>6.2}%  {i:>number_width$}  {instr:<16} {code}"
+            )?;
+        }
+
+        for (&i, histograms) in &profile.histograms {
+            writeln!(f, "\nRegisters at {i} ({} times):", profile.counts[i])?;
+            for (r, histogram) in histograms.iter().enumerate() {
+                let mut values: Vec<_> = histogram.iter().collect();
+                values.sort_by_key(|&(&value, &count)| (std::cmp::Reverse(count), value));
+                match values[..] {
+                    [] => break,
+                    [(value, _)] => writeln!(f, "  r{r}: always {value}")?,
+                    _ => {
+                        let frequent: Vec<_> = values
+                            .iter()
+                            .take(5)
+                            .map(|(value, count)| format!("{value} ({count})"))
+                            .collect();
+                        writeln!(
+                            f,
+                            "  r{r}: {} values, most frequent {}",
+                            values.len(),
+                            frequent.join(", ")
+                        )?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::{parse_input, FaultKind};
+
+    // r0 = sum of 1..=r5
+    const PROGRAM: &str = "\
+#ip 4
+seti 5 0 5
+addi 1 1 1
+addr 0 1 0
+eqrr 1 5 2
+addr 2 4 4
+seti 0 0 4
+";
+
+    #[test]
+    fn counts_and_histograms() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let profile = profile(ip, &instructions, [0; 6], &[2], None).unwrap();
+        assert!(profile.halted);
+        assert_eq!(profile.counts, [1, 5, 5, 5, 5, 4]);
+        assert_eq!(profile.steps, 25);
+        let histograms = &profile.histograms[&2];
+        assert_eq!(histograms[1], (1..=5).map(|v| (v, 1)).collect());
+        assert_eq!(histograms[5], HashMap::from([(5, 5)]));
+    }
+
+    #[test]
+    fn limit() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let profile = profile(ip, &instructions, [0; 6], &[], Some(10)).unwrap();
+        assert!(!profile.halted);
+        assert_eq!(profile.steps, 10);
+    }
+
+    #[test]
+    fn report() {
+        let (ip, instructions) = parse_input(PROGRAM.to_string()).unwrap();
+        let profile = profile(ip, &instructions, [0; 6], &[3], None).unwrap();
+        let report = Report {
+            ip,
+            instructions: &instructions,
+            profile: &profile,
+            top: 2,
+        };
+        assert_eq!(
+            report.to_string(),
+            "\
+25 instructions run, halted
+
+Hot spots:
+5  20.00%  1  addi 1 1 1       r1 = r1 + 1
+5  20.00%  2  addr 0 1 0       r0 = r0 + r1
+
+Registers at 3 (5 times):
+  r0: 5 values, most frequent 1 (1), 3 (1), 6 (1), 10 (1), 15 (1)
+  r1: 5 values, most frequent 1 (1), 2 (1), 3 (1), 4 (1), 5 (1)
+  r2: always 0
+  r3: always 0
+  r4: always 3
+  r5: always 5
+"
+        );
+    }
+
+    #[test]
+    fn fault() {
+        let (ip, instructions) = parse_input("#ip 5\naddr 9 0 0".to_string()).unwrap();
+        let fault = profile(ip, &instructions, [0; 6], &[0], None).unwrap_err();
+        assert_eq!((fault.kind, fault.pc), (FaultKind::InvalidRegister(9), 0));
+    }
+}
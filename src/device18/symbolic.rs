@@ -0,0 +1,432 @@
+use std::{collections::HashMap, fmt};
+
+use super::{
+    optimise::{Idiom, Optimised},
+    FaultKind, Instr, Op, Operand,
+};
+
+/// Value of a register, in terms of the values of the registers when the
+/// execution started.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Number(usize),
+    /// Initial value of the register
+    Register(usize),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+    /// Sum of the divisors of a positive number
+    DivisorSum(Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Mul,
+    Div,
+    And,
+    Or,
+    /// 1 if greater, else 0
+    Gt,
+    /// 1 if equal, else 0
+    Eq,
+}
+
+impl Operator {
+    fn new(op: Op) -> Option<Self> {
+        match op {
+            Op::Addr | Op::Addi => Some(Operator::Add),
+            Op::Mulr | Op::Muli => Some(Operator::Mul),
+            Op::Banr | Op::Bani => Some(Operator::And),
+            Op::Borr | Op::Bori => Some(Operator::Or),
+            Op::Setr | Op::Seti => None,
+            Op::Gtir | Op::Gtri | Op::Gtrr => Some(Operator::Gt),
+            Op::Eqir | Op::Eqri | Op::Eqrr => Some(Operator::Eq),
+        }
+    }
+
+    fn is_commutative(self) -> bool {
+        matches!(
+            self,
+            Operator::Add | Operator::Mul | Operator::And | Operator::Or | Operator::Eq
+        )
+    }
+
+    /// `None` on overflow, as a [`Machine`](super::Machine) would fault.
+    fn apply(self, a: usize, b: usize) -> Option<usize> {
+        match self {
+            Operator::Add => a.checked_add(b),
+            Operator::Mul => a.checked_mul(b),
+            Operator::Div => a.checked_div(b),
+            Operator::And => Some(a & b),
+            Operator::Or => Some(a | b),
+            Operator::Gt => Some((a > b) as usize),
+            Operator::Eq => Some((a == b) as usize),
+        }
+    }
+}
+
+fn divisor_sum(n: usize) -> Option<usize> {
+    let mut total: usize = 0;
+    let mut d = 1;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            total = total.checked_add(d)?;
+            if n / d != d {
+                total = total.checked_add(n / d)?;
+            }
+        }
+        d += 1;
+    }
+    Some(total)
+}
+
+impl Expr {
+    /// `a op b`, simplified: numbers are computed, and kept on the right of
+    /// commutative operators so that `(x + 1) + 2` becomes `x + 3`.
+    pub fn binary(a: Expr, op: Operator, b: Expr) -> Expr {
+        use Expr::Number;
+        let (a, b) = match (a, b) {
+            (a @ Number(_), b) if op.is_commutative() && !matches!(b, Number(_)) => (b, a),
+            ab => ab,
+        };
+        if let (Number(x), Number(y)) = (&a, &b) {
+            if let Some(value) = op.apply(*x, *y) {
+                return Number(value);
+            }
+        }
+        match (a, op, b) {
+            (a, Operator::Add | Operator::Or, Number(0)) => a,
+            (a, Operator::Mul | Operator::Div, Number(1)) => a,
+            (_, Operator::Mul | Operator::And, Number(0)) => Number(0),
+            (Expr::Binary(x, inner, y), _, Number(n))
+                if inner == op && matches!(op, Operator::Add | Operator::Mul) =>
+            {
+                let folded = match y.as_ref() {
+                    Number(m) => op.apply(*m, n),
+                    _ => None,
+                };
+                match folded {
+                    Some(m) => Expr::binary(*x, op, Number(m)),
+                    None => {
+                        Expr::Binary(Box::new(Expr::Binary(x, inner, y)), op, Box::new(Number(n)))
+                    }
+                }
+            }
+            (a, Operator::Eq, b) if a == b => Number(1),
+            (a, Operator::Gt, b) if a == b => Number(0),
+            (a, op, b) => Expr::Binary(Box::new(a), op, Box::new(b)),
+        }
+    }
+
+    fn divisor_sum(n: Expr) -> Expr {
+        match n {
+            Expr::Number(n) if n > 0 => match divisor_sum(n) {
+                Some(sum) => Expr::Number(sum),
+                None => Expr::DivisorSum(Box::new(Expr::Number(n))),
+            },
+            n => Expr::DivisorSum(Box::new(n)),
+        }
+    }
+}
+
+/// Why an [`Execution`] stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The next instruction would be this one, out of the program
+    Halted(usize),
+    /// The next instruction would be this one, which already ran: the
+    /// program loops
+    Loop(usize),
+    /// The instruction `at` jumps to an instruction depending on the
+    /// initial values of the registers
+    Jump {
+        at: usize,
+        target: Expr,
+    },
+    Fault {
+        at: usize,
+        kind: FaultKind,
+    },
+}
+
+/// Registers after running part of a program with symbolic values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    pub ip: usize,
+    pub start: usize,
+    pub initial: [Expr; 6],
+    pub registers: [Expr; 6],
+    /// Instructions run, each [`Idiom`] counting as one
+    pub steps: usize,
+    pub stop: Stop,
+}
+
+/// Run a program from the instruction `start` with the `initial` registers,
+/// as long as the jumps only depend on numbers and do not go back to an
+/// instruction already run. The loops replaced by the [`Optimised`]
+/// interpreter are run symbolically, assuming the usual cases: a quotient
+/// starting at 0, divisors from 1 of a positive number.
+pub fn execute(ip: usize, instructions: &[Instr], start: usize, initial: [Expr; 6]) -> Execution {
+    let optimised = Optimised::new(ip, instructions);
+    let idioms: HashMap<_, _> = optimised
+        .superinstructions()
+        .map(|(i, idiom)| (i, *idiom))
+        .collect();
+    let mut registers = initial.clone();
+    let mut visited = vec![false; instructions.len()];
+    let mut pointer = start;
+    let mut steps = 0;
+    let stop = loop {
+        let Some(instr) = instructions.get(pointer) else {
+            break Stop::Halted(pointer);
+        };
+        if std::mem::replace(&mut visited[pointer], true) {
+            break Stop::Loop(pointer);
+        }
+        registers[ip] = Expr::Number(pointer);
+        steps += 1;
+        if let Some(exit) = idioms
+            .get(&pointer)
+            .and_then(|idiom| apply(idiom, &mut registers))
+        {
+            pointer = exit;
+            continue;
+        }
+        let [a, b] = instr.op.operands();
+        let operand = |kind, field| match kind {
+            Operand::Register => registers.get(field).cloned().ok_or(field),
+            Operand::Immediate | Operand::Ignored => Ok(Expr::Number(field)),
+        };
+        let value = match (operand(a, instr.a), operand(b, instr.b)) {
+            _ if instr.c >= registers.len() => Err(instr.c),
+            (Ok(a), Ok(b)) => Ok(match Operator::new(instr.op) {
+                Some(op) => Expr::binary(a, op, b),
+                None => a,
+            }),
+            (Err(r), _) | (_, Err(r)) => Err(r),
+        };
+        let kind = match value {
+            Ok(value) => {
+                registers[instr.c] = value;
+                match &registers[ip] {
+                    Expr::Number(n) => match n.checked_add(1) {
+                        Some(next) => {
+                            pointer = next;
+                            continue;
+                        }
+                        None => FaultKind::IpOutOfRange(ip),
+                    },
+                    target => {
+                        let target = Expr::binary(target.clone(), Operator::Add, Expr::Number(1));
+                        break Stop::Jump {
+                            at: pointer,
+                            target,
+                        };
+                    }
+                }
+            }
+            Err(r) => FaultKind::InvalidRegister(r),
+        };
+        break Stop::Fault { at: pointer, kind };
+    };
+    Execution {
+        ip,
+        start,
+        initial,
+        registers,
+        steps,
+        stop,
+    }
+}
+
+/// Run an idiom on symbolic registers, returning the instruction after it,
+/// or `None` if it is not one of its usual cases.
+fn apply(idiom: &Idiom, registers: &mut [Expr; 6]) -> Option<usize> {
+    match *idiom {
+        Idiom::Divide {
+            quotient,
+            scratch,
+            dividend,
+            divisor,
+            exit,
+        } => {
+            if registers[quotient] != Expr::Number(0) || divisor == 0 {
+                return None;
+            }
+            let dividend = registers[dividend].clone();
+            registers[quotient] = Expr::binary(dividend, Operator::Div, Expr::Number(divisor));
+            registers[scratch] = Expr::Number(1);
+            Some(exit)
+        }
+        Idiom::DivisorSum {
+            x,
+            y,
+            product,
+            n,
+            sum,
+            exit,
+        } => {
+            if registers[x] != Expr::Number(1) || registers[n] == Expr::Number(0) {
+                return None;
+            }
+            let n = registers[n].clone();
+            let divisors = Expr::divisor_sum(n.clone());
+            registers[sum] = Expr::binary(registers[sum].clone(), Operator::Add, divisors);
+            registers[x] = Expr::binary(n.clone(), Operator::Add, Expr::Number(1));
+            registers[y] = registers[x].clone();
+            registers[product] = Expr::Number(1);
+            Some(exit)
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::Add => "+",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::And => "&",
+            Operator::Or => "|",
+            Operator::Gt => ">",
+            Operator::Eq => "==",
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter<'_>, e: &Expr| match e {
+            Expr::Binary(..) => write!(f, "({e})"),
+            _ => write!(f, "{e}"),
+        };
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Register(r) => write!(f, "r{r}"),
+            Expr::Binary(a, op, b) => {
+                operand(f, a)?;
+                write!(f, " {op} ")?;
+                operand(f, b)
+            }
+            Expr::DivisorSum(n) => write!(f, "divisor_sum({n})"),
+        }
+    }
+}
+
+/// How the execution stopped, then the registers whose values changed,
+/// but for the instruction pointer.
+impl fmt::Display for Execution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} instructions run from {}, ", self.steps, self.start)?;
+        match &self.stop {
+            Stop::Halted(pointer) => writeln!(f, "then halted going to {pointer}")?,
+            Stop::Loop(pointer) => writeln!(f, "then looped back to {pointer}")?,
+            Stop::Jump { at, target } => writeln!(f, "then {at} jumps to {target}")?,
+            Stop::Fault { at, kind } => writeln!(f, "then {at} faulted: {kind}")?,
+        }
+        for (r, (value, initial)) in self.registers.iter().zip(&self.initial).enumerate() {
+            if r != self.ip && value != initial {
+                writeln!(f, "r{r} = {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::parse_input;
+
+    fn unknown() -> [Expr; 6] {
+        [0, 1, 2, 3, 4, 5].map(Expr::Register)
+    }
+
+    #[test]
+    fn straight_line() {
+        let program = "#ip 4\nseti 2 0 5\nmulr 5 5 5\nmuli 5 19 5\naddi 5 3 5\naddr 5 0 5\n";
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        let mut initial = [0, 0, 0, 0, 0, 0].map(Expr::Number);
+        initial[0] = Expr::Register(0);
+        let execution = execute(ip, &instructions, 0, initial.clone());
+        assert_eq!(execution.stop, Stop::Halted(5));
+        assert_eq!(execution.registers[5].to_string(), "r0 + 79");
+        initial[0] = Expr::Number(5);
+        let execution = execute(ip, &instructions, 0, initial);
+        assert_eq!(execution.registers[5], Expr::Number(84));
+    }
+
+    #[test]
+    fn divide() {
+        // As in day 21, r1 = r3 / 256 with a loop
+        let program = "\
+#ip 2
+seti 0 0 1
+addi 1 1 4
+muli 4 256 4
+gtrr 4 3 4
+addr 4 2 2
+addi 2 1 2
+seti 8 0 2
+addi 1 1 1
+seti 0 0 2
+setr 1 0 5
+gtri 5 9 4
+addr 4 2 2
+";
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        let execution = execute(ip, &instructions, 0, unknown());
+        assert_eq!(
+            execution.to_string(),
+            "\
+5 instructions run from 0, then 11 jumps to ((r3 / 256) > 9) + 12
+r1 = r3 / 256
+r4 = (r3 / 256) > 9
+r5 = r3 / 256
+"
+        );
+    }
+
+    #[test]
+    fn divisor_sum_and_loop() {
+        // As in day 19, r0 += divisor_sum(r5) with nested loops
+        let program = "\
+#ip 3
+seti 1 0 1
+seti 1 0 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 1 0 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 0 0 3
+mulr 3 3 3
+";
+        let (ip, instructions) = parse_input(program.to_string()).unwrap();
+        let execution = execute(ip, &instructions, 0, unknown());
+        assert_eq!(execution.stop, Stop::Halted(226));
+        assert_eq!(execution.steps, 3);
+        assert_eq!(execution.registers[0].to_string(), "r0 + divisor_sum(r5)");
+        assert_eq!(execution.registers[1].to_string(), "r5 + 1");
+
+        let mut initial = [0, 0, 0, 0, 0, 36].map(Expr::Number);
+        initial[0] = Expr::Register(0);
+        let execution = execute(ip, &instructions, 0, initial);
+        assert_eq!(execution.registers[0].to_string(), "r0 + 91");
+
+        // Without the idiom, the loop stops the execution
+        let mut initial = unknown();
+        initial[1] = Expr::Number(2);
+        let execution = execute(ip, &instructions, 1, initial);
+        assert_eq!(
+            execution.to_string().lines().next(),
+            Some("4 instructions run from 1, then 4 jumps to (r5 == 2) + 5")
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::{fmt, str::FromStr};
+
+use super::{parse_ip, Fault, Instr, Machine};
+use crate::parse::{self, Line, ParseError, Span};
+
+/// A step of a traced run: the instruction run, or the first instruction of
+/// an idiom run in one step, and the registers around it.
+///
+/// Written as one line `<steps> <pc> <instruction> | <before> | <after>`,
+/// with the registers separated by spaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// Number of plain instructions run at the end of the step
+    pub steps: u64,
+    /// Number of the instruction
+    pub pc: usize,
+    pub instr: Instr,
+    /// Registers as the instruction sees them, the bound one holding `pc`
+    pub before: [usize; 6],
+    pub after: [usize; 6],
+}
+
+fn write_registers(f: &mut fmt::Formatter<'_>, registers: &[usize; 6]) -> fmt::Result {
+    for (r, value) in registers.iter().enumerate() {
+        match r {
+            0 => write!(f, "{value}")?,
+            _ => write!(f, " {value}")?,
+        }
+    }
+    Ok(())
+}
+
+fn parse_registers(line: &Line, part: Span) -> Result<[usize; 6], ParseError> {
+    let fields: Vec<_> = part.split(" ").collect();
+    let fields: [Span; 6] = fields
+        .try_into()
+        .map_err(|_| line.error(part, "6 registers"))?;
+    let mut registers = [0; 6];
+    for (register, field) in registers.iter_mut().zip(fields) {
+        *register = line.parse(field, "a number")?;
+    }
+    Ok(registers)
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} | ", self.steps, self.pc, self.instr)?;
+        write_registers(f, &self.before)?;
+        write!(f, " | ")?;
+        write_registers(f, &self.after)
+    }
+}
+
+impl FromStr for Event {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::first(s);
+        let expected = "\"<steps> <pc> <instruction> | <registers> | <registers>\"";
+        let [step, before, after] = line.span().split(" | ").collect::<Vec<_>>()[..] else {
+            return Err(line.invalid(expected));
+        };
+        let fields: Vec<_> = step.split(" ").collect();
+        let [steps, pc, instr @ ..] = &fields[..] else {
+            return Err(line.invalid(expected));
+        };
+        Ok(Event {
+            steps: line.parse(*steps, "a number of steps")?,
+            pc: line.parse(*pc, "an instruction number")?,
+            instr: Instr::from_fields(&line, instr)?,
+            before: parse_registers(&line, before)?,
+            after: parse_registers(&line, after)?,
+        })
+    }
+}
+
+/// Read a trace written as a line `#ip <register>` followed by one line per
+/// [`Event`].
+pub fn parse_trace(input: &str) -> Result<(usize, Vec<Event>), ParseError> {
+    let mut lines = parse::lines(input);
+    let ip = parse_ip(lines.expect("\"#ip <register>\"")?)?;
+    let events = lines
+        .map(|line| line.parse_with())
+        .collect::<Result<_, _>>()?;
+    Ok((ip, events))
+}
+
+/// Iterator over the [`Event`]s of a machine run with a step function, see
+/// [`trace`].
+pub struct Tracer<'m, 'a, S> {
+    machine: &'m mut Machine<'a, 6>,
+    step: S,
+    limit: u64,
+    faulted: bool,
+}
+
+/// Run `machine` with `step` (e.g. [`Machine::try_step`] or
+/// [`Optimised::try_step`](super::optimise::Optimised::try_step)) until it
+/// halts, faults or has run at least `limit` instructions, yielding each
+/// step as an [`Event`], then the fault if there is one.
+pub fn trace<'m, 'a, S>(machine: &'m mut Machine<'a, 6>, step: S, limit: u64) -> Tracer<'m, 'a, S>
+where
+    S: FnMut(&mut Machine<'a, 6>) -> Result<bool, Fault<6>>,
+{
+    Tracer {
+        machine,
+        step,
+        limit,
+        faulted: false,
+    }
+}
+
+impl<'a, S> Iterator for Tracer<'_, 'a, S>
+where
+    S: FnMut(&mut Machine<'a, 6>) -> Result<bool, Fault<6>>,
+{
+    type Item = Result<Event, Fault<6>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.faulted || self.machine.steps >= self.limit {
+            return None;
+        }
+        let pc = self.machine.pointer;
+        let instr = *self.machine.next()?;
+        let before = self.machine.registers_seen();
+        match (self.step)(self.machine) {
+            Ok(_) => Some(Ok(Event {
+                steps: self.machine.steps,
+                pc,
+                instr,
+                before,
+                after: self.machine.registers,
+            })),
+            Err(fault) => {
+                self.faulted = true;
+                Some(Err(fault))
+            }
+        }
+    }
+}
+
+/// First point where two traces of a program disagree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Number of plain instructions run by both traces before it
+    pub steps: u64,
+    /// Step of each trace at that point, `None` where the trace is over
+    pub left: Option<Event>,
+    pub right: Option<Event>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Traces diverge after {} instructions", self.steps)?;
+        for (side, event) in [("<", &self.left), (">", &self.right)] {
+            match event {
+                Some(event) => writeln!(f, "{side} {event}")?,
+                None => writeln!(f, "{side} end of trace")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare two traces of the same program, e.g. one run by the plain
+/// interpreter and one with idioms, where each has run the same number of
+/// plain instructions. A trace ending inside an idiom of the other one is
+/// only shorter, not divergent.
+pub fn diff(left: &[Event], right: &[Event]) -> Option<Divergence> {
+    // Number of instructions run before the current step of each trace
+    let (mut i, mut j) = (0, 0);
+    let (mut left_start, mut right_start) = (0, 0);
+    loop {
+        let divergence = |steps, left: Option<&Event>, right: Option<&Event>| Divergence {
+            steps,
+            left: left.copied(),
+            right: right.copied(),
+        };
+        match (left.get(i), right.get(j)) {
+            (None, None) => return None,
+            (Some(l), None) => {
+                return (left_start >= right_start).then(|| divergence(right_start, Some(l), None));
+            }
+            (None, Some(r)) => {
+                return (right_start >= left_start).then(|| divergence(left_start, None, Some(r)));
+            }
+            (Some(l), Some(r)) => {
+                if left_start == right_start && (l.pc, l.before) != (r.pc, r.before) {
+                    return Some(divergence(left_start, Some(l), Some(r)));
+                }
+                if l.steps == r.steps && l.after != r.after {
+                    return Some(divergence(left_start.min(right_start), Some(l), Some(r)));
+                }
+                if l.steps <= r.steps {
+                    left_start = l.steps;
+                    i += 1;
+                }
+                if r.steps <= l.steps {
+                    right_start = r.steps;
+                    j += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device18::{optimise::Optimised, parse_input};
+
+    // for r1 in 1..=r5 { for r4 in 1..=r5 { if r1 * r4 == r5 { r0 += r1 } } }
+    const DIVISOR_SUM_PROGRAM: &str = "\
+#ip 3
+seti 1 0 1
+seti 1 0 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 1 0 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 0 0 3
+mulr 3 3 3
+";
+
+    fn events<'a>(
+        machine: &mut Machine<'a, 6>,
+        step: impl FnMut(&mut Machine<'a, 6>) -> Result<bool, Fault<6>>,
+        limit: u64,
+    ) -> Vec<Event> {
+        trace(machine, step, limit).map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let (ip, instructions) = parse_input(DIVISOR_SUM_PROGRAM.to_string()).unwrap();
+        let mut machine = Machine::new(Some(ip), &instructions);
+        machine.registers[5] = 6;
+        let events = events(&mut machine, Machine::try_step, 3);
+        let text: String = std::iter::once(format!("#ip {ip}"))
+            .chain(events.iter().map(Event::to_string))
+            .map(|line| line + "\n")
+            .collect();
+        assert_eq!(
+            text,
+            "#ip 3\n\
+             1 0 seti 1 0 1 | 0 0 0 0 0 6 | 0 1 0 0 0 6\n\
+             2 1 seti 1 0 4 | 0 1 0 1 0 6 | 0 1 0 1 1 6\n\
+             3 2 mulr 1 4 2 | 0 1 0 2 1 6 | 0 1 1 2 1 6\n"
+        );
+        assert_eq!(parse_trace(&text), Ok((ip, events)));
+        assert_eq!(
+            parse_trace("#ip 3\n1 0 seti 1 0 1 | 0 0 0 0 0 6 | 0 1 0 0 6\n"),
+            Err(ParseError::new(2, 32, "0 1 0 0 6", "6 registers"))
+        );
+    }
+
+    #[test]
+    fn optimised_matches_plain() {
+        let (ip, instructions) = parse_input(DIVISOR_SUM_PROGRAM.to_string()).unwrap();
+        let optimised = Optimised::new(ip, &instructions);
+        let mut machine = optimised.machine();
+        machine.registers[5] = 36;
+        let plain = events(&mut machine.clone(), Machine::try_step, u64::MAX);
+        let fast = events(&mut machine, |m| optimised.try_step(m), u64::MAX);
+        assert!(fast.len() < plain.len());
+        assert_eq!(fast.last().unwrap().after[0], 91);
+        assert_eq!(diff(&plain, &fast), None);
+
+        // Limited to fewer instructions than the idiom stands for
+        let plain = events(&mut optimised.machine(), Machine::try_step, 10);
+        let fast = events(&mut optimised.machine(), |m| optimised.try_step(m), 10);
+        assert_eq!(diff(&plain, &fast), None);
+    }
+
+    #[test]
+    fn first_divergence() {
+        let (ip, instructions) = parse_input(DIVISOR_SUM_PROGRAM.to_string()).unwrap();
+        let mut machine = Machine::new(Some(ip), &instructions);
+        machine.registers[5] = 6;
+        let reference = events(&mut machine.clone(), Machine::try_step, 100);
+        // Forgets to increment r4 the second time round
+        let broken = events(
+            &mut machine,
+            |m| match (m.pointer, m.steps) {
+                (7, 14..) if m.steps < 20 => {
+                    m.registers[ip] = 7;
+                    m.pointer = 8;
+                    m.steps += 1;
+                    Ok(true)
+                }
+                _ => m.try_step(),
+            },
+            100,
+        );
+        let divergence = diff(&reference, &broken).unwrap();
+        assert_eq!(divergence.steps, 14);
+        let (left, right) = (divergence.left.unwrap(), divergence.right.unwrap());
+        assert_eq!((left.pc, left.steps), (7, 15));
+        assert_eq!((left.after[4], right.after[4]), (3, 2));
+
+        let divergence = diff(&reference, &reference[..50]).unwrap();
+        assert_eq!((divergence.steps, divergence.right), (50, None));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    env, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Environment variable overriding the directory of the inputs.
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS";
+
+/// Where the input of a puzzle is read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Source given on the command line: `-` is stdin, anything else a path.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+
+    /// Default input of a puzzle, `{year}_{day:02}.txt` in the directory of
+    /// `profile`.
+    pub fn default_for(year: u16, day: u8, profile: Option<&str>) -> Self {
+        Source::File(profile_dir(profile).join(format!("{year}_{day:02}.txt")))
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Source::File(path) => fs::read_to_string(path),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+/// Directory of the inputs: `$AOC_INPUTS` if set, else `inputs`, found
+/// with [`crate_relative`].
+pub fn inputs_dir() -> PathBuf {
+    match env::var_os(INPUTS_DIR_VAR) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => crate_relative("inputs"),
+    }
+}
+
+/// Directory of the inputs of `profile`, a named set of inputs (e.g. those
+/// of one user) stored in a subdirectory of [`inputs_dir`]. The default set
+/// is directly in [`inputs_dir`].
+pub fn profile_dir(profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => inputs_dir().join(name),
+        None => inputs_dir(),
+    }
+}
+
+/// Profiles with at least one input for `year`: the default set first,
+/// then the named ones sorted by name.
+pub fn profiles(year: u16) -> io::Result<Vec<Option<String>>> {
+    let dir = inputs_dir();
+    let has_inputs = |dir: &Path| -> io::Result<bool> {
+        let prefix = format!("{year}_");
+        for entry in fs::read_dir(dir)? {
+            let name = entry?.file_name();
+            if name.to_string_lossy().starts_with(&prefix) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    };
+
+    let mut named = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && has_inputs(&entry.path())? {
+            named.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    named.sort();
+
+    let default = has_inputs(&dir)?.then_some(None);
+    Ok(default
+        .into_iter()
+        .chain(named.into_iter().map(Some))
+        .collect())
+}
+
+/// Root of the crate, where it was built from.
+pub fn crate_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// `path` in the working directory if it exists there, else in the root of
+/// the crate, so that binaries and tests can be run from anywhere.
+pub fn crate_relative(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if path.is_absolute() || path.exists() {
+        return path.to_path_buf();
+    }
+    crate_root().join(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_from_arg() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("day1.txt"),
+            Source::File(PathBuf::from("day1.txt"))
+        );
+        assert_eq!(Source::Stdin.to_string(), "<stdin>");
+    }
+
+    #[test]
+    fn crate_relative_fallback() {
+        let path = crate_relative("src/inputs.rs");
+        assert!(path.exists());
+        let missing = crate_relative("no/such/file");
+        assert_eq!(
+            missing,
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("no/such/file")
+        );
+    }
+}
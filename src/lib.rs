@@ -1,2 +1,22 @@
+pub mod answers;
+pub mod bench;
 pub mod device18;
+pub mod inputs;
+pub mod output;
 pub mod parse;
+pub mod scaffold;
+pub mod solution;
+pub mod year2018;
+
+pub use solution::{Answer, Puzzle, Solution};
+
+/// Registry of all solved puzzles, ordered by year and day.
+static PUZZLES: &[&[Puzzle]] = &[year2018::PUZZLES];
+
+pub fn puzzles() -> impl Iterator<Item = &'static Puzzle> {
+    PUZZLES.iter().flat_map(|year| year.iter())
+}
+
+pub fn puzzle(year: u16, day: u8) -> Option<&'static Puzzle> {
+    puzzles().find(|p| p.year == year && p.day == day)
+}
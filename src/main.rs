@@ -1 +1,952 @@
-fn main() {}
+use rustventofcode::{
+    answers::{Answers, Status},
+    bench::{self, Pretty},
+    device18::{
+        self, asm,
+        cfg::Cfg,
+        compile,
+        debugger::Debugger,
+        decompile::Decompiled,
+        disasm::Listing,
+        halting::{self, End},
+        opcodes,
+        optimise::Optimised,
+        profile::{self, Report},
+        symbolic::{self, Expr},
+        trace, Instr, Op,
+    },
+    inputs::{self, Source},
+    output::{self, Format, Record},
+    scaffold,
+    year2018::day16,
+    Answer, Puzzle,
+};
+use std::{
+    env, fs,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process,
+};
+
+const USAGE: &str = "\
+Usage: rustventofcode [YEAR [DAY [PART]]]
+       rustventofcode [--year YEAR] [--day DAY] [--part PART] [--check]
+                      [--time] [--repeat N] [--report FILE] [--input FILE]
+                      [--profile NAME | --all-profiles] [--format FORMAT]
+       rustventofcode new-day YEAR DAY
+       rustventofcode disasm (DAY | FILE | -)
+       rustventofcode asm (FILE | -)
+       rustventofcode cfg [--dot] (DAY | FILE | -)
+       rustventofcode decompile [--rust] (DAY | FILE | -)
+       rustventofcode debug (DAY | FILE)
+       rustventofcode profile [--at I]... [--set rR=V]... [--limit N]
+                              [--top N] (DAY | FILE | -)
+       rustventofcode symbolic [--from I] [--set rR=V]... (DAY | FILE | -)
+       rustventofcode trace [--optimised] [--set rR=V]... [--limit N]
+                            (DAY | FILE | -)
+       rustventofcode trace-diff FILE FILE
+       rustventofcode opcodes [FILE | -]
+       rustventofcode halting [--input R] (DAY | FILE | -)
+       rustventofcode device-bench [--limit N] [--repeat N] [DAY | FILE]...
+
+Runs the solutions of DAY (or of every day) of YEAR (default: latest year).
+PART is 1, 2 or all (default: all).
+
+Inputs are read from inputs/YEAR_DD.txt, in the directory given by the
+AOC_INPUTS environment variable, else in the working directory if it has
+an inputs directory, else in the root of the crate. --input FILE reads the
+input of the selected DAY from FILE instead, or from stdin if FILE is -.
+
+Each user can keep their own inputs in a profile, inputs/NAME/YEAR_DD.txt,
+with answers in answers/NAME/YEAR.toml. --profile NAME uses them instead
+of the default ones. --all-profiles runs every profile and shows their
+answers side by side.
+
+With --check, answers are compared with answers/YEAR.toml and the exit
+status is non-zero if any of them is wrong.
+
+With --time, the time taken by each part is shown, followed by a summary
+table of the parse, part 1 and part 2 times of each day. --repeat N runs
+every day N times and reports the median and minimum times. --report FILE
+also writes the timings to FILE, as JSON if it ends in .json, else as CSV.
+--repeat and --report imply --time.
+
+--format json or tsv replaces the banners by one record per answer, with
+its status (if checked) and median times: JSON objects, one per line, or
+tab-separated values after a header line. The default format is text.
+
+new-day creates the module of DAY of YEAR in the source tree, registers
+it (and YEAR if it is new) with the runner, and creates an empty input.
+
+disasm prints the device program of 2018 DAY (19 or 21), of FILE or of
+stdin as numbered pseudo-code.
+
+asm assembles a device program written with labels, named registers,
+comments and jump macros (see device18::asm) and prints it in the format
+of the inputs. The other device subcommands also assemble FILEs ending in
+.asm.
+
+cfg prints the basic blocks of a device program, their successors and its
+loops, or with --dot the control-flow graph in the Graphviz DOT language.
+
+decompile prints a device program as structured pseudo-code, with if and
+loops instead of jumps, or with --rust as a Rust function taking and
+returning the registers.
+
+debug runs a device program under a debugger reading its commands from
+stdin, with breakpoints, watchpoints and instruction counts (see help).
+
+profile runs a device program until it halts, or for at most --limit N
+(default: 10^8) instructions, and reports the --top N (default: 20)
+instructions run the most, and the values of the registers before each
+instruction --at I. --set rR=V sets register R to V before running.
+
+symbolic runs a device program from instruction --from I (default: 0)
+with unknown register values, until it halts, loops or a jump depends on
+them, and prints the registers as formulas of their initial values r0 to
+r5. From instruction 0, only r0 is unknown and the others are 0. --set
+rR=V sets register R to V instead.
+
+trace runs a device program until it halts, or for at most --limit N
+(default: 10^5) instructions, and prints each instruction run with the
+registers before and after it. With --optimised, idioms such as divisions
+run in one step, as when solving the days. --set rR=V sets register R to V
+before running.
+
+trace-diff compares two traces of a program, e.g. a plain and an optimised
+one, at the points where both have run the same number of instructions,
+and reports the first difference. Its exit status is 1 if there is one.
+
+opcodes identifies the operations of the opcodes of device samples in the
+format of 2018 day 16 (default: its input), and explains how each one was
+deduced, along with the samples contradicting the others.
+
+halting finds the instructions comparing register --input R (default: 0)
+with another register, where the program uses R for nothing else, and
+prints the values of R halting the program, by number of instructions run
+before halting, until the program repeats itself.
+
+device-bench times device programs (default: days 19 and 21) run by the
+plain interpreter and compiled to closures, until they halt or for at most
+--limit N (default: 10^8) instructions, --repeat N (default: 5) times.";
+
+struct Args {
+    year: u16,
+    day: Option<u8>,
+    parts: Vec<u8>,
+    check: bool,
+    time: bool,
+    repeat: usize,
+    report: Option<PathBuf>,
+    input: Option<Source>,
+    profile: Option<String>,
+    all_profiles: bool,
+    /// None for text
+    format: Option<Format>,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut year = None;
+        let mut day = None;
+        let mut part = None;
+        let mut check = false;
+        let mut time = false;
+        let mut repeat = None;
+        let mut report = None;
+        let mut input = None;
+        let mut profile = None;
+        let mut all_profiles = false;
+        let mut format = None;
+        let mut positional = Vec::new();
+
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let slot = match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    process::exit(0);
+                }
+                "--year" => &mut year,
+                "--day" => &mut day,
+                "--part" => &mut part,
+                "--repeat" => &mut repeat,
+                "--report" => &mut report,
+                "--input" => &mut input,
+                "--profile" => &mut profile,
+                "--format" => &mut format,
+                "--check" => {
+                    check = true;
+                    continue;
+                }
+                "--time" => {
+                    time = true;
+                    continue;
+                }
+                "--all-profiles" => {
+                    all_profiles = true;
+                    continue;
+                }
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                _ => {
+                    positional.push(arg);
+                    continue;
+                }
+            };
+            let value = args.next().ok_or(format!("Missing value for {arg}"))?;
+            *slot = Some(value);
+        }
+
+        let mut positional = positional.into_iter();
+        for slot in [&mut year, &mut day, &mut part] {
+            if let Some(value) = positional.next() {
+                if slot.is_some() {
+                    return Err(format!("Argument given twice: {value}"));
+                }
+                *slot = Some(value);
+            }
+        }
+        if let Some(value) = positional.next() {
+            return Err(format!("Unexpected argument: {value}"));
+        }
+
+        let year = match year {
+            Some(year) => year.parse().map_err(|_| format!("Invalid year: {year}"))?,
+            None => rustventofcode::puzzles().last().unwrap().year,
+        };
+        let day = match day.as_deref() {
+            None | Some("all") => None,
+            Some(day) => match day.parse() {
+                Ok(d @ 1..=25) => Some(d),
+                _ => return Err(format!("Invalid day: {day}")),
+            },
+        };
+        let parts = match part.as_deref() {
+            None | Some("all") => vec![1, 2],
+            Some("1") => vec![1],
+            Some("2") => vec![2],
+            Some(part) => return Err(format!("Invalid part: {part}")),
+        };
+        if input.is_some() && day.is_none() {
+            return Err("--input needs a DAY".to_string());
+        }
+        if all_profiles {
+            let conflict = [
+                ("--profile", profile.is_some()),
+                ("--input", input.is_some()),
+                ("--time", time),
+                ("--repeat", repeat.is_some()),
+                ("--report", report.is_some()),
+                ("--format", format.is_some()),
+            ];
+            if let Some((flag, _)) = conflict.iter().find(|(_, given)| *given) {
+                return Err(format!("--all-profiles cannot be used with {flag}"));
+            }
+        }
+        let format = match format.as_deref() {
+            None | Some("text") => None,
+            Some(format) => Some(format.parse()?),
+        };
+        let time = time || repeat.is_some() || report.is_some();
+        let repeat = match repeat {
+            None => 1,
+            Some(n) => match n.parse() {
+                Ok(n) if n > 0 => n,
+                _ => return Err(format!("Invalid number of runs: {n}")),
+            },
+        };
+        Ok(Args {
+            year,
+            day,
+            parts,
+            check,
+            time,
+            repeat,
+            report: report.map(PathBuf::from),
+            input: input.as_deref().map(Source::from_arg),
+            profile,
+            all_profiles,
+            format,
+        })
+    }
+}
+
+fn main() {
+    match env::args().nth(1).as_deref() {
+        Some("new-day") => return new_day(env::args().skip(2)),
+        Some("disasm") => return disasm(env::args().skip(2)),
+        Some("asm") => return assemble(env::args().skip(2)),
+        Some("cfg") => return cfg(env::args().skip(2)),
+        Some("decompile") => return decompile(env::args().skip(2)),
+        Some("debug") => return debug(env::args().skip(2)),
+        Some("profile") => return profile(env::args().skip(2)),
+        Some("symbolic") => return symbolic(env::args().skip(2)),
+        Some("trace") => return record_trace(env::args().skip(2)),
+        Some("trace-diff") => return trace_diff(env::args().skip(2)),
+        Some("opcodes") => return identify_opcodes(env::args().skip(2)),
+        Some("halting") => return halting(env::args().skip(2)),
+        Some("device-bench") => return device_bench(env::args().skip(2)),
+        _ => (),
+    }
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    let puzzles: Vec<_> = rustventofcode::puzzles()
+        .filter(|p| p.year == args.year && args.day.is_none_or(|day| p.day == day))
+        .collect();
+    if puzzles.is_empty() {
+        match args.day {
+            Some(day) => eprintln!("No solution for {} day {day}", args.year),
+            None => eprintln!("No solutions for year {}", args.year),
+        }
+        process::exit(1);
+    }
+
+    if args.all_profiles {
+        compare_profiles(&args, &puzzles);
+        return;
+    }
+
+    let answers = load_answers(&args, args.profile.as_deref());
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    let mut timings = Vec::new();
+    let mut invalid_inputs = 0;
+    if let Some(format) = args.format {
+        output::write_header(format, io::stdout()).unwrap();
+    }
+
+    for puzzle in puzzles {
+        let (year, day) = (puzzle.year, puzzle.day);
+        let source = match &args.input {
+            Some(source) => source.clone(),
+            None => Source::default_for(year, day, args.profile.as_deref()),
+        };
+        let input = match source.read() {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("{source}: {err}");
+                invalid_inputs += 1;
+                continue;
+            }
+        };
+
+        if args.format.is_none() {
+            println!("{year} day {day}");
+            println!("================");
+        }
+        let mut results = Vec::new();
+        let report = |part, answer, elapsed| {
+            let status = args.check.then(|| answers.check(day, part, &answer));
+            let mut line = match answer {
+                // Drawings start on their own line
+                Answer::Text(ref s) if s.contains('\n') => format!("Part {part}:\n{s}"),
+                _ => format!("Part {part}: {answer}"),
+            };
+            if let Some(status) = &status {
+                match status {
+                    Status::Pass => passed += 1,
+                    Status::Fail { .. } => failed += 1,
+                    Status::Missing => missing += 1,
+                }
+                line += &format!(" [{status}]");
+            }
+            if args.time {
+                line += &format!(" ({})", Pretty(elapsed));
+            }
+            match args.format {
+                None => println!("{line}"),
+                Some(_) => results.push((part, answer, status)),
+            }
+        };
+        match bench::run(puzzle, &input, &args.parts, args.repeat, report) {
+            Ok(t) => {
+                if let Some(format) = args.format {
+                    for ((part, answer, status), (_, samples)) in results.iter().zip(&t.parts) {
+                        let record = Record {
+                            year,
+                            day,
+                            part: *part,
+                            answer,
+                            status: status.as_ref(),
+                            runs: samples.runs(),
+                            parse: t.parse.median(),
+                            time: samples.median(),
+                        };
+                        output::write_record(format, &record, io::stdout()).unwrap();
+                    }
+                }
+                timings.push(t);
+            }
+            Err(err) => {
+                eprintln!("{source}: {err}");
+                invalid_inputs += 1;
+            }
+        }
+    }
+
+    if args.time && args.format.is_none() {
+        println!();
+        bench::print_summary(&timings);
+    }
+    if let Some(path) = &args.report {
+        let result = fs::File::create(path).and_then(|file| {
+            match path.extension().is_some_and(|ext| ext == "json") {
+                true => bench::write_json(&timings, file),
+                false => bench::write_csv(&timings, file),
+            }
+        });
+        if let Err(err) = result {
+            eprintln!("{}: {err}", path.display());
+            process::exit(1);
+        }
+    }
+
+    if args.check {
+        if args.format.is_none() {
+            println!();
+            println!("{passed} passed, {failed} failed, {missing} missing");
+        }
+        if failed > 0 {
+            process::exit(1);
+        }
+    }
+    if invalid_inputs > 0 {
+        process::exit(1);
+    }
+}
+
+fn load_answers(args: &Args, profile: Option<&str>) -> Answers {
+    if !args.check {
+        return Answers::default();
+    }
+    Answers::load(args.year, profile).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    })
+}
+
+/// Run `puzzles` on the inputs of every profile and print the answers in a
+/// table with a column per profile. Missing inputs are shown as `-`.
+fn compare_profiles(args: &Args, puzzles: &[&Puzzle]) {
+    let profiles = inputs::profiles(args.year).unwrap_or_else(|err| {
+        eprintln!("{}: {err}", inputs::inputs_dir().display());
+        process::exit(1);
+    });
+    if profiles.is_empty() {
+        eprintln!("No inputs for year {}", args.year);
+        process::exit(1);
+    }
+    let answers: Vec<_> = profiles
+        .iter()
+        .map(|profile| load_answers(args, profile.as_deref()))
+        .collect();
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    let mut invalid_inputs = 0;
+
+    let mut header = vec!["Day".to_string()];
+    header.extend(
+        profiles
+            .iter()
+            .map(|profile| profile.as_deref().unwrap_or("default").to_string()),
+    );
+    let mut rows = vec![header];
+    for puzzle in puzzles {
+        let mut puzzle_rows: Vec<_> = args
+            .parts
+            .iter()
+            .map(|part| vec![format!("{}.{part}", puzzle.day)])
+            .collect();
+        for (profile, answers) in profiles.iter().zip(&answers) {
+            let source = Source::default_for(puzzle.year, puzzle.day, profile.as_deref());
+            let mut cells = vec!["-".to_string(); args.parts.len()];
+            match source.read() {
+                Ok(input) => {
+                    let report = |part, answer, _| {
+                        let mut cell = format!("{answer}");
+                        match answers.check(puzzle.day, part, &answer) {
+                            Status::Pass => passed += 1,
+                            Status::Fail { .. } => {
+                                failed += 1;
+                                cell += " [FAIL]";
+                            }
+                            Status::Missing => missing += 1,
+                        }
+                        let i = args.parts.iter().position(|&p| p == part).unwrap();
+                        cells[i] = cell;
+                    };
+                    if let Err(err) = bench::run(puzzle, &input, &args.parts, 1, report) {
+                        eprintln!("{source}: {err}");
+                        invalid_inputs += 1;
+                        cells.fill("invalid".to_string());
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => {
+                    eprintln!("{source}: {err}");
+                    invalid_inputs += 1;
+                }
+            }
+            for (row, cell) in puzzle_rows.iter_mut().zip(cells) {
+                row.push(cell);
+            }
+        }
+        rows.extend(puzzle_rows);
+    }
+
+    let widths: Vec<_> = (0..rows[0].len())
+        .map(|i| rows.iter().map(|row| row[i].chars().count()).max().unwrap())
+        .collect();
+    for row in &rows {
+        let cells: Vec<_> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+
+    if args.check {
+        println!();
+        println!("{passed} passed, {failed} failed, {missing} missing");
+    }
+    if failed > 0 || invalid_inputs > 0 {
+        process::exit(1);
+    }
+}
+
+fn new_day(args: impl Iterator<Item = String>) {
+    let args: Vec<_> = args.collect();
+    let (year, day) = match &args[..] {
+        [year, day] => match (year.parse(), day.parse()) {
+            (Ok(year), Ok(day)) => (year, day),
+            _ => {
+                eprintln!("Invalid year or day: {year} {day}\n\n{USAGE}");
+                process::exit(2);
+            }
+        },
+        _ => {
+            eprintln!("new-day needs a YEAR and a DAY\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    match scaffold::new_day(inputs::crate_root(), &inputs::inputs_dir(), year, day) {
+        Ok(changed) => {
+            for path in changed {
+                println!("{}", path.display());
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Device program named by the arguments of a subcommand: a day of 2018, a
+/// file (assembled if it ends in `.asm`) or `-` for stdin.
+fn device_program(args: impl Iterator<Item = String>) -> (usize, Vec<Instr>) {
+    let args: Vec<_> = args.collect();
+    let [arg] = &args[..] else {
+        eprintln!("Expected a DAY, a FILE or -\n\n{USAGE}");
+        process::exit(2);
+    };
+    let source = match arg.parse() {
+        Ok(day) => Source::default_for(2018, day, None),
+        Err(_) => Source::from_arg(arg),
+    };
+    let input = source.read().unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    });
+    let program = match arg.ends_with(".asm") {
+        true => asm::assemble(&input),
+        false => device18::parse_input(input),
+    };
+    program.unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    })
+}
+
+fn assemble(args: impl Iterator<Item = String>) {
+    let args: Vec<_> = args.collect();
+    let [arg] = &args[..] else {
+        eprintln!("Expected a FILE or -\n\n{USAGE}");
+        process::exit(2);
+    };
+    let source = Source::from_arg(arg);
+    let input = source.read().unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    });
+    let (ip, instructions) = asm::assemble(&input).unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    });
+    print!("{}", asm::to_input(ip, &instructions));
+}
+
+fn disasm(args: impl Iterator<Item = String>) {
+    let (ip, instructions) = device_program(args);
+    print!(
+        "{}",
+        Listing {
+            ip,
+            instructions: &instructions
+        }
+    );
+}
+
+fn cfg(args: impl Iterator<Item = String>) {
+    let (dot, args): (Vec<_>, Vec<_>) = args.partition(|arg| arg == "--dot");
+    let (ip, instructions) = device_program(args.into_iter());
+    let cfg = Cfg::new(ip, &instructions);
+    match dot.is_empty() {
+        true => print!("{cfg}"),
+        false => print!("{}", cfg.to_dot()),
+    }
+}
+
+fn decompile(args: impl Iterator<Item = String>) {
+    let (rust, args): (Vec<_>, Vec<_>) = args.partition(|arg| arg == "--rust");
+    let (ip, instructions) = device_program(args.into_iter());
+    let decompiled = Decompiled::new(ip, &instructions);
+    match rust.is_empty() {
+        true => print!("{decompiled}"),
+        false => match decompiled.to_rust("program") {
+            Ok(rust) => print!("{rust}"),
+            Err(err) => {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn debug(args: impl Iterator<Item = String>) {
+    let args: Vec<_> = args.collect();
+    if args.iter().any(|arg| arg == "-") {
+        eprintln!("debug reads its commands from stdin, the program must be a DAY or a FILE");
+        process::exit(2);
+    }
+    let (ip, instructions) = device_program(args.into_iter());
+    let mut debugger = Debugger::new(ip, &instructions);
+    if let Err(err) = debugger.repl(io::stdin().lock(), io::stdout()) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+/// Value of a `--set rR=V` argument.
+fn assignment(value: Option<String>) -> (usize, usize) {
+    let value = value.unwrap_or_default();
+    let assignment = value
+        .strip_prefix('r')
+        .and_then(|value| value.split_once('='))
+        .and_then(|(r, v)| Some((r.parse().ok().filter(|&r| r < 6)?, v.parse().ok()?)));
+    assignment.unwrap_or_else(|| {
+        eprintln!("Invalid value for --set: {value}\n\n{USAGE}");
+        process::exit(2);
+    })
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(value)) => value,
+        _ => {
+            eprintln!("Invalid or missing value for {flag}\n\n{USAGE}");
+            process::exit(2);
+        }
+    }
+}
+
+/// Check that the instruction numbers given with `flag` are in a program of
+/// `len` instructions.
+fn check_instruction_numbers(flag: &str, numbers: &[usize], len: usize) -> Result<(), String> {
+    match numbers.iter().find(|&&i| i >= len) {
+        Some(i) => Err(format!(
+            "Invalid value for {flag}: {i}, the program has {len} instructions"
+        )),
+        None => Ok(()),
+    }
+}
+
+fn profile(args: impl Iterator<Item = String>) {
+    let mut at = Vec::new();
+    let mut set = Vec::new();
+    let mut limit = 10u64.pow(8);
+    let mut top = 20;
+    let mut program = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--at" => at.push(parse_value("--at", args.next())),
+            "--limit" => limit = parse_value("--limit", args.next()),
+            "--top" => top = parse_value("--top", args.next()),
+            "--set" => set.push(assignment(args.next())),
+            _ => program.push(arg),
+        }
+    }
+
+    let (ip, instructions) = device_program(program.into_iter());
+    if let Err(err) = check_instruction_numbers("--at", &at, instructions.len()) {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    }
+    let mut registers = [0; 6];
+    for (r, value) in set {
+        registers[r] = value;
+    }
+    let profile =
+        profile::profile(ip, &instructions, registers, &at, Some(limit)).unwrap_or_else(|fault| {
+            eprintln!("{fault}");
+            process::exit(1);
+        });
+    if !profile.halted {
+        eprintln!("The program did not halt within {limit} instructions, see --limit");
+    }
+    print!(
+        "{}",
+        Report {
+            ip,
+            instructions: &instructions,
+            profile: &profile,
+            top,
+        }
+    );
+}
+
+fn symbolic(args: impl Iterator<Item = String>) {
+    let mut from = 0;
+    let mut set = Vec::new();
+    let mut program = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = parse_value("--from", args.next()),
+            "--set" => set.push(assignment(args.next())),
+            _ => program.push(arg),
+        }
+    }
+
+    let (ip, instructions) = device_program(program.into_iter());
+    let mut registers = [0, 1, 2, 3, 4, 5].map(Expr::Register);
+    if from == 0 {
+        registers[1..].fill(Expr::Number(0));
+    }
+    for (r, value) in set {
+        registers[r] = Expr::Number(value);
+    }
+    print!("{}", symbolic::execute(ip, &instructions, from, registers));
+}
+
+fn record_trace(args: impl Iterator<Item = String>) {
+    let mut optimise = false;
+    let mut set = Vec::new();
+    let mut limit = 10u64.pow(5);
+    let mut program = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--optimised" => optimise = true,
+            "--limit" => limit = parse_value("--limit", args.next()),
+            "--set" => set.push(assignment(args.next())),
+            _ => program.push(arg),
+        }
+    }
+
+    let (ip, instructions) = device_program(program.into_iter());
+    let optimised = Optimised::new(ip, &instructions);
+    let mut machine = optimised.machine();
+    for (r, value) in set {
+        machine.registers[r] = value;
+    }
+    let step = |machine: &mut _| match optimise {
+        true => optimised.try_step(machine),
+        false => device18::Machine::try_step(machine),
+    };
+    let mut out = BufWriter::new(io::stdout().lock());
+    let written = writeln!(out, "#ip {ip}").and_then(|()| {
+        for event in trace::trace(&mut machine, step, limit) {
+            match event {
+                Ok(event) => writeln!(out, "{event}")?,
+                Err(fault) => {
+                    out.flush()?;
+                    eprintln!("{fault}");
+                    process::exit(1);
+                }
+            }
+        }
+        out.flush()
+    });
+    if let Err(err) = written {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+fn trace_diff(args: impl Iterator<Item = String>) {
+    let args: Vec<_> = args.collect();
+    let [left, right] = &args[..] else {
+        eprintln!("Expected two FILEs\n\n{USAGE}");
+        process::exit(2);
+    };
+    let [left, right] = [left, right].map(|path| {
+        let source = Source::from_arg(path);
+        let input = source.read().unwrap_or_else(|err| {
+            eprintln!("{source}: {err}");
+            process::exit(1);
+        });
+        trace::parse_trace(&input).unwrap_or_else(|err| {
+            eprintln!("{source}: {err}");
+            process::exit(1);
+        })
+    });
+    if left.0 != right.0 {
+        eprintln!("The traces bind different registers to the instruction pointer");
+        process::exit(1);
+    }
+    match trace::diff(&left.1, &right.1) {
+        Some(divergence) => {
+            print!("{divergence}");
+            process::exit(1);
+        }
+        None => println!("No divergence"),
+    }
+}
+
+fn identify_opcodes(args: impl Iterator<Item = String>) {
+    let args: Vec<_> = args.collect();
+    let source = match &args[..] {
+        [] => Source::default_for(2018, 16, None),
+        [arg] => Source::from_arg(arg),
+        _ => {
+            eprintln!("Expected at most one FILE or -\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    let input = source.read().unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    });
+    let (samples, _) = day16::parse_input(input).unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    });
+    let identification = opcodes::identify(&Op::ALL, &samples);
+    print!("{identification}");
+    println!(
+        "{} of {} samples match several operations",
+        identification.ambiguous().count(),
+        samples.len()
+    );
+}
+
+fn halting(args: impl Iterator<Item = String>) {
+    let mut input = 0;
+    let mut program = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = parse_value("--input", args.next()),
+            _ => program.push(arg),
+        }
+    }
+    if input >= 6 {
+        eprintln!("Invalid value for --input: {input}\n\n{USAGE}");
+        process::exit(2);
+    }
+
+    let (ip, instructions) = device_program(program.into_iter());
+    let optimised = Optimised::new(ip, &instructions);
+    let mut values = halting::halting_values(&optimised, input).unwrap_or_else(|err| {
+        eprintln!("Unsupported program: {err}");
+        process::exit(1);
+    });
+    let mut count = 0;
+    for halting in values.by_ref() {
+        match halting {
+            Ok(halting) => println!("r{input} = {halting}"),
+            Err(fault) => {
+                eprintln!("{fault}");
+                process::exit(1);
+            }
+        }
+        count += 1;
+    }
+    match values.end() {
+        Some(End::Repeats) => {
+            println!("{count} values, the program runs forever for any other")
+        }
+        Some(End::Halts(steps)) => {
+            println!("{count} values, the program halts after {steps} instructions for any other")
+        }
+        None => unreachable!("halting values end with the program"),
+    }
+}
+
+fn device_bench(args: impl Iterator<Item = String>) {
+    let mut limit = 10u64.pow(8);
+    let mut repeat = 5;
+    let mut programs = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--limit" => &mut limit,
+            "--repeat" => &mut repeat,
+            _ => {
+                programs.push(arg);
+                continue;
+            }
+        };
+        match args.next().map(|n| n.parse()) {
+            Some(Ok(n)) => *value = n,
+            _ => {
+                eprintln!("Invalid or missing value for {arg}\n\n{USAGE}");
+                process::exit(2);
+            }
+        }
+    }
+    if programs.is_empty() {
+        programs = vec!["19".to_string(), "21".to_string()];
+    }
+
+    for program in programs {
+        let (ip, instructions) = device_program(std::iter::once(program.clone()));
+        let benchmark = compile::benchmark(ip, &instructions, limit, repeat as usize)
+            .unwrap_or_else(|err| {
+                eprintln!("{program}: {err}");
+                process::exit(1);
+            });
+        let (interpreted, compiled) = (&benchmark.interpreted, &benchmark.compiled);
+        let speedup = interpreted.median().as_secs_f64() / compiled.median().as_secs_f64();
+        println!("{program}: {} instructions", benchmark.steps);
+        println!(
+            "  interpreted {:>9} / {:>9}",
+            Pretty(interpreted.median()),
+            Pretty(interpreted.min())
+        );
+        println!(
+            "  compiled    {:>9} / {:>9}  ({speedup:.2}x)",
+            Pretty(compiled.median()),
+            Pretty(compiled.min())
+        );
+    }
+    println!("(median / min over {} runs)", repeat.max(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instruction_numbers() {
+        assert_eq!(check_instruction_numbers("--at", &[0, 20], 21), Ok(()));
+        assert_eq!(
+            check_instruction_numbers("--at", &[3, 99], 21),
+            Err("Invalid value for --at: 99, the program has 21 instructions".to_string())
+        );
+    }
+}
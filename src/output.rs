@@ -0,0 +1,163 @@
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{answers::Status, Answer};
+
+/// Machine-readable output format of the runner, instead of its banners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One JSON object per line and per answer
+    Json,
+    /// Tab-separated values, with a header line
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("Invalid format: {s}")),
+        }
+    }
+}
+
+/// Result of one part of a puzzle.
+pub struct Record<'a> {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: &'a Answer,
+    /// Only if the answers are checked
+    pub status: Option<&'a Status>,
+    pub runs: usize,
+    /// Median times
+    pub parse: Duration,
+    pub time: Duration,
+}
+
+const TSV_HEADER: &str = "year\tday\tpart\tanswer\tstatus\texpected\truns\tparse_ns\ttime_ns";
+
+/// Header to write before the records, if `format` has one.
+pub fn write_header(format: Format, mut w: impl Write) -> io::Result<()> {
+    match format {
+        Format::Tsv => writeln!(w, "{TSV_HEADER}"),
+        Format::Json => Ok(()),
+    }
+}
+
+pub fn write_record(format: Format, record: &Record, mut w: impl Write) -> io::Result<()> {
+    let (status, expected) = match record.status {
+        None => (None, None),
+        Some(Status::Pass) => (Some("pass"), None),
+        Some(Status::Fail { expected }) => (Some("fail"), Some(expected.as_str())),
+        Some(Status::Missing) => (Some("missing"), None),
+    };
+    match format {
+        Format::Json => {
+            let answer = match record.answer {
+                Answer::Number(n) => n.to_string(),
+                Answer::Text(s) => json_string(s),
+            };
+            let optional = |s: Option<&str>| s.map_or("null".to_string(), json_string);
+            writeln!(
+                w,
+                r#"{{"year": {}, "day": {}, "part": {}, "answer": {}, "status": {}, "expected": {}, "runs": {}, "parse_ns": {}, "time_ns": {}}}"#,
+                record.year,
+                record.day,
+                record.part,
+                answer,
+                optional(status),
+                optional(expected),
+                record.runs,
+                record.parse.as_nanos(),
+                record.time.as_nanos(),
+            )
+        }
+        Format::Tsv => writeln!(
+            w,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            record.year,
+            record.day,
+            record.part,
+            tsv_field(&record.answer.to_string()),
+            status.unwrap_or(""),
+            tsv_field(expected.unwrap_or("")),
+            record.runs,
+            record.parse.as_nanos(),
+            record.time.as_nanos(),
+        ),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Multi-line answers (e.g. letters drawn in a grid) are kept on one line.
+fn tsv_field(s: &str) -> String {
+    s.replace(['\t', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(format: Format, answer: &Answer, status: Option<&Status>) -> String {
+        let record = Record {
+            year: 2018,
+            day: 2,
+            part: 1,
+            answer,
+            status,
+            runs: 3,
+            parse: Duration::from_nanos(10),
+            time: Duration::from_nanos(200),
+        };
+        let mut out = Vec::new();
+        write_record(format, &record, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn json_record() {
+        let status = Status::Fail {
+            expected: "ab".to_string(),
+        };
+        assert_eq!(
+            write(Format::Json, &"a\"b".into(), Some(&status)),
+            r#"{"year": 2018, "day": 2, "part": 1, "answer": "a\"b", "status": "fail", "expected": "ab", "runs": 3, "parse_ns": 10, "time_ns": 200}"#
+                .to_string()
+                + "\n"
+        );
+        assert!(write(Format::Json, &42.into(), None)
+            .contains(r#""answer": 42, "status": null, "expected": null"#));
+    }
+
+    #[test]
+    fn tsv_record() {
+        assert_eq!(
+            write(Format::Tsv, &"a\tb".into(), Some(&Status::Pass)),
+            "2018\t2\t1\ta b\tpass\t\t3\t10\t200\n"
+        );
+        assert_eq!(TSV_HEADER.split('\t').count(), 9);
+    }
+}
@@ -1,6 +1,307 @@
+use std::{error::Error, fmt, ops::Range, str::FromStr};
+
 pub fn to_strings(input: String) -> Vec<String> {
     // Can't just return Vec<str>
     // input.lines().collect()
     // because input is owned by parse_input
     input.lines().map(|line| line.to_string()).collect()
 }
+
+/// Error in the input of a puzzle, pointing at the offending text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    /// Offending text, empty if there is none (e.g. the input is truncated)
+    pub text: String,
+    /// Description of what was expected instead
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(
+        line: usize,
+        column: usize,
+        text: impl Into<String>,
+        expected: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            line,
+            column,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Error for input that ends before `line`.
+    pub fn end_of_input(line: usize, expected: impl Into<String>) -> Self {
+        ParseError::new(line, 1, "", expected)
+    }
+
+    /// Move an error found in a part of the input that starts `lines` lines
+    /// after the beginning of the whole input.
+    pub fn offset(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        match self.text.as_str() {
+            "" => write!(f, ", found nothing"),
+            text => write!(f, ", found {text:?}"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Iterator over the numbered lines of an input.
+pub struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    number: usize,
+}
+
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines {
+        lines: input.lines(),
+        number: 0,
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.lines.next()?;
+        self.number += 1;
+        Some(Line {
+            number: self.number,
+            text,
+        })
+    }
+}
+
+impl<'a> Lines<'a> {
+    /// Next line, or an error if the input is over.
+    pub fn expect(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        let number = self.number + 1;
+        self.next()
+            .ok_or_else(|| ParseError::end_of_input(number, expected))
+    }
+
+    /// The remaining lines, or an error if there are none, for inputs that
+    /// are lists of at least one `expected`.
+    pub fn non_empty(self, expected: &str) -> Result<Self, ParseError> {
+        match self.lines.clone().next() {
+            Some(_) => Ok(self),
+            None => Err(ParseError::end_of_input(self.number + 1, expected)),
+        }
+    }
+}
+
+/// A line of the input, with its 1-based line number.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// The first line of `s`, for errors in [`FromStr`] implementations that
+    /// callers can then move with [`ParseError::offset`].
+    pub fn first(s: &'a str) -> Self {
+        Line { number: 1, text: s }
+    }
+
+    /// The whole line, to take the parts to report errors on from.
+    pub fn span(&self) -> Span<'a> {
+        Span {
+            start: 0,
+            text: self.text,
+        }
+    }
+
+    /// Error on `part`, a [`Span`] of this line.
+    pub fn error(&self, part: Span, expected: impl Into<String>) -> ParseError {
+        let column = self.text[..part.start].chars().count() + 1;
+        ParseError::new(self.number, column, part.text, expected)
+    }
+
+    /// Error on the whole line.
+    pub fn invalid(&self, expected: impl Into<String>) -> ParseError {
+        self.error(self.span(), expected)
+    }
+
+    /// Parse `part`, a [`Span`] of this line.
+    pub fn parse<T: FromStr>(&self, part: Span, expected: &str) -> Result<T, ParseError> {
+        part.text.parse().map_err(|_| self.error(part, expected))
+    }
+
+    /// Parse the whole line with a [`FromStr`] implementation returning
+    /// errors relative to the line.
+    pub fn parse_with<T: FromStr<Err = ParseError>>(&self) -> Result<T, ParseError> {
+        self.text
+            .parse()
+            .map_err(|err: ParseError| err.offset(self.number - 1))
+    }
+}
+
+/// Part of a [`Line`], with the byte offset where it starts in the line so
+/// that errors on it point at the right column. Spans are taken from
+/// [`Line::span`] with the methods below, which keep track of the offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub start: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// The part in `range`, a byte range of the text.
+    pub fn slice(self, range: Range<usize>) -> Self {
+        Span {
+            start: self.start + range.start,
+            text: &self.text[range],
+        }
+    }
+
+    /// The part matched by a regex run on the text.
+    pub fn at(self, m: regex::Match) -> Self {
+        self.slice(m.range())
+    }
+
+    pub fn trim(self) -> Self {
+        self.trim_start().trim_end()
+    }
+
+    pub fn trim_start(self) -> Self {
+        let rest = self.text.trim_start();
+        self.slice(self.text.len() - rest.len()..self.text.len())
+    }
+
+    pub fn trim_end(self) -> Self {
+        self.slice(0..self.text.trim_end().len())
+    }
+
+    pub fn trim_start_matches(self, c: char) -> Self {
+        let rest = self.text.trim_start_matches(c);
+        self.slice(self.text.len() - rest.len()..self.text.len())
+    }
+
+    pub fn trim_end_matches(self, c: char) -> Self {
+        self.slice(0..self.text.trim_end_matches(c).len())
+    }
+
+    pub fn strip_prefix(self, prefix: &str) -> Option<Self> {
+        let rest = self.text.strip_prefix(prefix)?;
+        Some(self.slice(prefix.len()..prefix.len() + rest.len()))
+    }
+
+    pub fn strip_suffix(self, suffix: &str) -> Option<Self> {
+        let rest = self.text.strip_suffix(suffix)?;
+        Some(self.slice(0..rest.len()))
+    }
+
+    pub fn split_once(self, delimiter: &str) -> Option<(Self, Self)> {
+        let (before, _) = self.text.split_once(delimiter)?;
+        let after = before.len() + delimiter.len();
+        Some((
+            self.slice(0..before.len()),
+            self.slice(after..self.text.len()),
+        ))
+    }
+
+    /// Parts separated by `separator`, like [`str::split`].
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Self> + 'a {
+        let mut start = 0;
+        self.text.split(separator).map(move |part| {
+            let span = self.slice(start..start + part.len());
+            start += part.len() + separator.len();
+            span
+        })
+    }
+
+    /// Parts separated by whitespace, like [`str::split_whitespace`].
+    pub fn split_whitespace(self) -> impl Iterator<Item = Self> + 'a {
+        self.split(" ")
+            .flat_map(|part| part.split("\t"))
+            .filter(|part| !part.text.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_column() {
+        let line = Line {
+            number: 3,
+            text: "#1 @ 1,x: 4x4",
+        };
+        let err = line
+            .parse::<usize>(line.span().slice(7..8), "a number")
+            .unwrap_err();
+        assert_eq!(err, ParseError::new(3, 8, "x", "a number"));
+        assert_eq!(
+            err.to_string(),
+            r#"line 3, column 8: expected a number, found "x""#
+        );
+    }
+
+    #[test]
+    fn span_positions() {
+        // Columns count characters, starts count bytes
+        let line = Line::first("pos=<é1, -2> r=3");
+        let (pos, r) = line.span().split_once(" r=").unwrap();
+        let coords = pos
+            .strip_prefix("pos=<")
+            .unwrap()
+            .strip_suffix(">")
+            .unwrap();
+        let starts: Vec<_> = coords.split(",").map(|c| c.trim().start).collect();
+        assert_eq!(starts, [5, 10]);
+        assert_eq!(
+            line.error(r, "a number"),
+            ParseError::new(1, 16, "3", "a number")
+        );
+        let words: Vec<_> = line.span().split_whitespace().map(|w| w.start).collect();
+        assert_eq!(words, [0, 10, 14]);
+    }
+
+    #[test]
+    fn truncated_input() {
+        let mut lines = lines("a\nb\n");
+        lines.expect("a").unwrap();
+        lines.expect("b").unwrap();
+        let err = lines.expect("c").unwrap_err();
+        assert_eq!(err, ParseError::end_of_input(3, "c"));
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: expected c, found nothing"
+        );
+    }
+
+    #[test]
+    fn parse_with_offset() {
+        struct Digit;
+        impl FromStr for Digit {
+            type Err = ParseError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let line = Line::first(s);
+                line.parse::<u8>(line.span(), "a digit").map(|_| Digit)
+            }
+        }
+        let line = lines("1\n2\nz").nth(2).unwrap();
+        let err = line.parse_with::<Digit>().err().unwrap();
+        assert_eq!(err, ParseError::new(3, 1, "z", "a digit"));
+    }
+}
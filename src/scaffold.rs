@@ -0,0 +1,288 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Create the module of a new day in the crate at `root` and register it in
+/// the module of its year, creating and registering the year if needed, then
+/// create an empty input in `inputs_dir` unless there is one already.
+///
+/// Returns the files created or modified.
+pub fn new_day(root: &Path, inputs_dir: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("Invalid day: {day}"));
+    }
+    let src = root.join("src");
+    let day_path = src.join(format!("year{year}/day{day:02}.rs"));
+    if day_path.exists() {
+        return Err(format!("{} already exists", day_path.display()));
+    }
+    let year_path = src.join(format!("year{year}.rs"));
+    let lib_path = src.join("lib.rs");
+    let mut changed = Vec::new();
+
+    let mut days = match fs::read_to_string(&year_path) {
+        Ok(module) => module_numbers(&module, "pub mod day"),
+        Err(_) => {
+            let lib = read(&lib_path)?;
+            write(&lib_path, &register_year(&lib, year)?, &mut changed)?;
+            Vec::new()
+        }
+    };
+    // Declared already if only the module of the day was deleted
+    if !days.contains(&day.into()) {
+        days.push(day.into());
+        days.sort();
+    }
+
+    let day_dir = day_path.parent().unwrap();
+    fs::create_dir_all(day_dir).map_err(|err| format!("{}: {err}", day_dir.display()))?;
+    write(&day_path, &day_module(year, day), &mut changed)?;
+    write(&year_path, &year_module(&days), &mut changed)?;
+
+    let input_path = inputs_dir.join(format!("{year}_{day:02}.txt"));
+    if !input_path.exists() {
+        fs::create_dir_all(inputs_dir).map_err(|err| format!("{}: {err}", inputs_dir.display()))?;
+        write(&input_path, "", &mut changed)?;
+    }
+    Ok(changed)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn write(path: &Path, contents: &str, changed: &mut Vec<PathBuf>) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("{}: {err}", path.display()))?;
+    changed.push(path.to_path_buf());
+    Ok(())
+}
+
+/// Numbers of the modules declared as `{prefix}N;`, e.g. the days of a year.
+fn module_numbers(module: &str, prefix: &str) -> Vec<u16> {
+    module
+        .lines()
+        .filter_map(|line| line.strip_prefix(prefix)?.strip_suffix(';')?.parse().ok())
+        .collect()
+}
+
+/// Skeleton of the module of a day, with an ignored test for each part.
+fn day_module(year: u16, day: u8) -> String {
+    format!(
+        r#"use crate::{{
+    parse::{{to_strings, ParseError}},
+    Answer, Solution,
+}};
+
+pub struct Day{day:02};
+
+impl Solution for Day{day:02} {{
+    const YEAR: u16 = {year};
+    const DAY: u8 = {day};
+
+    type Parsed = Vec<String>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {{
+        parse_input(input)
+    }}
+
+    fn part1(parsed: &Self::Parsed) -> Answer {{
+        part1(parsed).into()
+    }}
+
+    fn part2(parsed: &Self::Parsed) -> Answer {{
+        part2(parsed).into()
+    }}
+}}
+
+pub fn parse_input(input: String) -> Result<Vec<String>, ParseError> {{
+    Ok(to_strings(input))
+}}
+
+pub fn part1(_lines: &[String]) -> usize {{
+    0
+}}
+
+pub fn part2(_lines: &[String]) -> usize {{
+    0
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const EXAMPLE: &str = "\
+";
+
+    #[test]
+    #[ignore = "example not filled in yet"]
+    fn part1_example() {{
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 0);
+    }}
+
+    #[test]
+    #[ignore = "example not filled in yet"]
+    fn part2_example() {{
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 0);
+    }}
+}}
+"#
+    )
+}
+
+/// Module of a year, declaring and registering `days`.
+fn year_module(days: &[u16]) -> String {
+    let mut module = String::new();
+    for day in days {
+        module += &format!("pub mod day{day:02};\n");
+    }
+    let puzzles: Vec<_> = days
+        .iter()
+        .map(|day| format!("Puzzle::of::<day{day:02}::Day{day:02}>()"))
+        .collect();
+    module += "\nuse crate::Puzzle;\n\n";
+    module += &static_slice("pub static PUZZLES: &[Puzzle]", &puzzles);
+    module + "\n"
+}
+
+/// `{declaration} = &[{items}];` on one line if it fits in 100 columns,
+/// else with an item per line, as formatted by rustfmt.
+fn static_slice(declaration: &str, items: &[String]) -> String {
+    let line = format!("{declaration} = &[{}];", items.join(", "));
+    if line.len() <= 100 {
+        return line;
+    }
+    let mut s = format!("{declaration} = &[\n");
+    for item in items {
+        s += &format!("    {item},\n");
+    }
+    s + "];"
+}
+
+/// Add the module of `year` to the declarations and the registry of
+/// `lib.rs`, keeping the years in order.
+fn register_year(lib: &str, year: u16) -> Result<String, String> {
+    let mut years = module_numbers(lib, "pub mod year");
+    if years.is_empty() {
+        return Err("No year module declared in lib.rs".to_string());
+    }
+    if !years.contains(&year) {
+        years.push(year);
+        years.sort();
+    }
+
+    let registry: Vec<_> = years.iter().map(|y| format!("year{y}::PUZZLES")).collect();
+    let registry = static_slice("static PUZZLES: &[&[Puzzle]]", &registry);
+
+    let mut out = String::new();
+    let mut lines = lib.lines();
+    let mut declared = false;
+    while let Some(line) = lines.next() {
+        if line.starts_with("pub mod year") {
+            if !declared {
+                for y in &years {
+                    out += &format!("pub mod year{y};\n");
+                }
+                declared = true;
+            }
+        } else if line.starts_with("static PUZZLES") {
+            if !line.ends_with(';') {
+                lines.by_ref().find(|line| line.ends_with("];"));
+            }
+            out += &registry;
+            out.push('\n');
+        } else {
+            out += line;
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod parse;
+pub mod year2018;
+
+pub use solution::Puzzle;
+
+/// Registry
+static PUZZLES: &[&[Puzzle]] = &[year2018::PUZZLES];
+
+pub fn puzzles() {}
+";
+
+    #[test]
+    fn year_module_registers_days() {
+        let module = year_module(&[1, 2, 12]);
+        assert!(module.starts_with("pub mod day01;\npub mod day02;\npub mod day12;\n"));
+        assert!(module.contains("    Puzzle::of::<day12::Day12>(),\n];\n"));
+        assert_eq!(module_numbers(&module, "pub mod day"), [1, 2, 12]);
+        assert!(year_module(&[4]).ends_with("&[Puzzle::of::<day04::Day04>()];\n"));
+    }
+
+    #[test]
+    fn register_new_year() {
+        let lib = register_year(LIB, 2017).unwrap();
+        assert_eq!(
+            lib,
+            LIB.replace("pub mod year2018;", "pub mod year2017;\npub mod year2018;")
+                .replace(
+                    "&[year2018::PUZZLES]",
+                    "&[year2017::PUZZLES, year2018::PUZZLES]"
+                )
+        );
+        assert_eq!(register_year(&lib, 2018).unwrap(), lib);
+    }
+
+    #[test]
+    fn register_many_years() {
+        let lib = (2015..2025).fold(LIB.to_string(), |lib, year| {
+            register_year(&lib, year).unwrap()
+        });
+        assert!(lib.contains("&[\n    year2015::PUZZLES,\n"));
+        assert!(lib.contains("    year2024::PUZZLES,\n];\n\npub fn puzzles"));
+        assert_eq!(register_year(&lib, 2016).unwrap(), lib);
+    }
+
+    #[test]
+    fn new_day_in_new_year() {
+        let root = std::env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        let inputs = root.join("inputs");
+
+        let changed = new_day(&root, &inputs, 2030, 3).unwrap();
+        assert_eq!(changed.len(), 4);
+        new_day(&root, &inputs, 2030, 1).unwrap();
+        let year = fs::read_to_string(root.join("src/year2030.rs")).unwrap();
+        assert_eq!(year, year_module(&[1, 3]));
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod year2030;"));
+        assert!(inputs.join("2030_03.txt").exists());
+        assert!(new_day(&root, &inputs, 2030, 3).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn new_day_already_declared() {
+        let root = std::env::temp_dir().join(format!("scaffold-declared-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        let inputs = root.join("inputs");
+
+        new_day(&root, &inputs, 2030, 1).unwrap();
+        new_day(&root, &inputs, 2030, 3).unwrap();
+        fs::remove_file(root.join("src/year2030/day03.rs")).unwrap();
+        new_day(&root, &inputs, 2030, 3).unwrap();
+        let year = fs::read_to_string(root.join("src/year2030.rs")).unwrap();
+        assert_eq!(year, year_module(&[1, 3]));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use std::{any::Any, fmt};
+
+use crate::parse::ParseError;
+
+/// A solution to the puzzle of one day.
+///
+/// The input is parsed once and shared by the two parts.
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+
+    type Parsed;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError>;
+    fn part1(parsed: &Self::Parsed) -> Answer;
+    fn part2(parsed: &Self::Parsed) -> Answer;
+}
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Number(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Number(n.try_into().expect("Answer does not fit in an i64"))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A type-erased [`Solution`], as stored in the registry.
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    parse: fn(String) -> Result<Box<dyn Any>, ParseError>,
+    part1: fn(&dyn Any) -> Answer,
+    part2: fn(&dyn Any) -> Answer,
+}
+
+impl Puzzle {
+    pub const fn of<S: Solution>() -> Self
+    where
+        S::Parsed: 'static,
+    {
+        Puzzle {
+            year: S::YEAR,
+            day: S::DAY,
+            parse: parse_any::<S>,
+            part1: part1_any::<S>,
+            part2: part2_any::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: String) -> Result<Box<dyn Any>, ParseError> {
+        (self.parse)(input)
+    }
+
+    /// Solve `part` (1 or 2) given the output of [`Puzzle::parse`].
+    pub fn solve(&self, part: u8, parsed: &dyn Any) -> Answer {
+        match part {
+            1 => (self.part1)(parsed),
+            2 => (self.part2)(parsed),
+            _ => panic!("Invalid part: {part}"),
+        }
+    }
+}
+
+fn parse_any<S: Solution>(input: String) -> Result<Box<dyn Any>, ParseError>
+where
+    S::Parsed: 'static,
+{
+    Ok(Box::new(S::parse(input)?))
+}
+
+fn downcast<S: Solution>(parsed: &dyn Any) -> &S::Parsed
+where
+    S::Parsed: 'static,
+{
+    parsed
+        .downcast_ref()
+        .expect("Parsed input belongs to another puzzle")
+}
+
+fn part1_any<S: Solution>(parsed: &dyn Any) -> Answer
+where
+    S::Parsed: 'static,
+{
+    S::part1(downcast::<S>(parsed))
+}
+
+fn part2_any<S: Solution>(parsed: &dyn Any) -> Answer
+where
+    S::Parsed: 'static,
+{
+    S::part2(downcast::<S>(parsed))
+}
@@ -0,0 +1,55 @@
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+use crate::Puzzle;
+
+pub static PUZZLES: &[Puzzle] = &[
+    Puzzle::of::<day01::Day01>(),
+    Puzzle::of::<day02::Day02>(),
+    Puzzle::of::<day03::Day03>(),
+    Puzzle::of::<day04::Day04>(),
+    Puzzle::of::<day05::Day05>(),
+    Puzzle::of::<day06::Day06>(),
+    Puzzle::of::<day07::Day07>(),
+    Puzzle::of::<day08::Day08>(),
+    Puzzle::of::<day09::Day09>(),
+    Puzzle::of::<day10::Day10>(),
+    Puzzle::of::<day11::Day11>(),
+    Puzzle::of::<day12::Day12>(),
+    Puzzle::of::<day13::Day13>(),
+    Puzzle::of::<day14::Day14>(),
+    Puzzle::of::<day15::Day15>(),
+    Puzzle::of::<day16::Day16>(),
+    Puzzle::of::<day17::Day17>(),
+    Puzzle::of::<day18::Day18>(),
+    Puzzle::of::<day19::Day19>(),
+    Puzzle::of::<day20::Day20>(),
+    Puzzle::of::<day21::Day21>(),
+    Puzzle::of::<day22::Day22>(),
+    Puzzle::of::<day23::Day23>(),
+    Puzzle::of::<day24::Day24>(),
+    Puzzle::of::<day25::Day25>(),
+];
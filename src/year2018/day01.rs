@@ -0,0 +1,88 @@
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 1;
+
+    type Parsed = Vec<i32>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+pub fn parse_input(input: String) -> Result<Vec<i32>, ParseError> {
+    parse::lines(&input)
+        .non_empty("a frequency change")?
+        .map(|line| line.parse(line.span(), "a frequency change"))
+        .collect()
+}
+
+pub fn part1(changes: &[i32]) -> i32 {
+    changes.iter().sum()
+}
+
+pub fn part2(changes: &[i32]) -> i32 {
+    let mut past_values = std::collections::HashSet::new();
+    let mut current_value = 0;
+
+    // for c in changes.iter().cycle() {}
+    let mut i = 0;
+    while past_values.insert(current_value) {
+        current_value += changes[i];
+        i = (i + 1) % changes.len();
+    }
+    current_value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(changes: &str) -> Vec<i32> {
+        parse_input(changes.replace(", ", "\n")).unwrap()
+    }
+
+    #[test]
+    fn invalid_change() {
+        let err = parse_input("+1\n+x\n".to_string()).unwrap_err();
+        assert_eq!(err, ParseError::new(2, 1, "+x", "a frequency change"));
+    }
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(part1(&parse("+1, -2, +3, +1")), 3);
+        assert_eq!(part1(&parse("+1, +1, +1")), 3);
+        assert_eq!(part1(&parse("+1, +1, -2")), 0);
+        assert_eq!(part1(&parse("-1, -2, -3")), -6);
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(part2(&parse("+1, -2, +3, +1")), 2);
+        assert_eq!(part2(&parse("+1, -1")), 0);
+        assert_eq!(part2(&parse("+3, +3, +4, -2, -4")), 10);
+        assert_eq!(part2(&parse("-6, +3, +8, +5, -6")), 5);
+        assert_eq!(part2(&parse("+7, +7, -2, -7, -4")), 14);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a frequency change"))
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    parse::{self, to_strings, ParseError},
+    Answer, Solution,
+};
+use std::collections::HashMap;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 2;
+
+    type Parsed = Vec<String>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<Vec<String>, ParseError> {
+    parse::lines(&input).non_empty("a box ID")?;
+    Ok(to_strings(input))
+}
+
+pub fn part1(ids: &[String]) -> i32 {
+    let mut count_two: u16 = 0;
+    let mut count_three: u16 = 0;
+
+    for id in ids.iter() {
+        let counter = count_chars(id);
+
+        if counter.values().any(|&count| count == 2) {
+            count_two += 1;
+        }
+        if counter.values().any(|&count| count == 3) {
+            count_three += 1;
+        }
+    }
+
+    (count_two * count_three) as i32
+}
+
+fn count_chars(s: &str) -> HashMap<char, u32> {
+    let mut counter = HashMap::new();
+    for c in s.chars() {
+        *counter.entry(c).or_insert(0) += 1;
+    }
+    counter
+}
+
+pub fn part2(strings: &[String]) -> String {
+    for i in 0..strings.len() {
+        for j in i + 1..strings.len() {
+            if differ_by_one(&strings[i], &strings[j]) {
+                return common_chars(&strings[i], &strings[j]);
+            }
+        }
+    }
+    panic!("No match found")
+}
+
+fn differ_by_one(s1: &str, s2: &str) -> bool {
+    s1.chars()
+        .zip(s2.chars())
+        .filter(|&(c1, c2)| c1 != c2)
+        .count()
+        == 1
+}
+
+fn common_chars(s1: &str, s2: &str) -> String {
+    s1.chars()
+        .zip(s2.chars())
+        .filter(|&(c1, c2)| c1 == c2)
+        .map(|(c1, _)| c1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        let input = "\
+abcdef
+bababc
+abbcde
+abcccd
+aabcdd
+abcdee
+ababab
+";
+        assert_eq!(part1(&parse_input(input.to_string()).unwrap()), 12);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = "\
+abcde
+fghij
+klmno
+pqrst
+fguij
+axcye
+wvxyz
+";
+        assert_eq!(part2(&parse_input(input.to_string()).unwrap()), "fgij");
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a box ID"))
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    cmp::{max, min},
+    collections::HashSet,
+    str::FromStr,
+};
+
+use crate::{
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 3;
+
+    type Parsed = Vec<Claim>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<Vec<Claim>, ParseError> {
+    parse::lines(&input)
+        .non_empty("a claim")?
+        .map(|line| line.parse_with())
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Claim {
+    id: usize,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+impl Claim {
+    fn xmax(&self) -> usize {
+        self.x + self.w - 1
+    }
+    fn ymax(&self) -> usize {
+        self.y + self.h - 1
+    }
+
+    fn contains(&self, x: &usize, y: &usize) -> bool {
+        self.x <= *x && *x <= self.xmax() && self.y <= *y && *y <= self.ymax()
+    }
+
+    fn overlaps(&self, other: &Claim) -> bool {
+        let overlaps_x = self.x <= other.xmax() && self.xmax() >= other.x;
+        let overlaps_y = self.y <= other.ymax() && self.ymax() >= other.y;
+        overlaps_x && overlaps_y
+    }
+}
+
+impl FromStr for Claim {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::first(s);
+        let parts: Vec<_> = line.span().split_whitespace().collect();
+        if parts.len() != 4 || parts[1].text != "@" {
+            return Err(line.invalid("a claim \"#<id> @ <x>,<y>: <w>x<h>\""));
+        }
+
+        let id = line.parse(parts[0].trim_start_matches('#'), "a claim id")?;
+        let (x, y) = parts[2]
+            .trim_end_matches(':')
+            .split_once(",")
+            .ok_or_else(|| line.error(parts[2], "a position \"<x>,<y>:\""))?;
+        let (w, h) = parts[3]
+            .split_once("x")
+            .ok_or_else(|| line.error(parts[3], "a size \"<w>x<h>\""))?;
+
+        Ok(Claim {
+            id,
+            x: line.parse(x, "a number")?,
+            y: line.parse(y, "a number")?,
+            w: line.parse(w, "a number")?,
+            h: line.parse(h, "a number")?,
+        })
+    }
+}
+
+pub fn part1(claims: &[Claim]) -> usize {
+    let mut count = 0;
+
+    // Find range
+    let (mut xmin, mut xmax) = (usize::MAX, usize::MIN);
+    let (mut ymin, mut ymax) = (usize::MAX, usize::MIN);
+    for c in claims {
+        xmin = min(xmin, c.x);
+        xmax = max(xmax, c.xmax());
+        ymin = min(ymin, c.y);
+        ymax = max(ymax, c.ymax());
+    }
+
+    for i in xmin..=xmax {
+        for j in ymin..=ymax {
+            // claims.iter().filter(|&c| *c.contains(&i, &j)).count();\
+            let mut overlap = 0;
+            for c in claims {
+                if c.contains(&i, &j) {
+                    overlap += 1;
+                    if overlap > 1 {
+                        count += 1;
+                        break;
+                    }
+                };
+            }
+        }
+    }
+
+    count
+}
+
+pub fn part2(claims: &[Claim]) -> usize {
+    let mut overlapping = HashSet::new();
+    for c1 in claims.iter() {
+        if overlapping.contains(&c1.id) {
+            continue;
+        }
+        let mut overlap = false;
+        for c2 in claims.iter() {
+            if c1.id != c2.id && c1.overlaps(c2) {
+                overlap = true;
+                overlapping.insert(c2.id);
+                break;
+            }
+        }
+        if !overlap {
+            return c1.id;
+        };
+    }
+    panic!("Non-overlapping claim not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#1 @ 1,3: 4x4
+#2 @ 3,1: 4x4
+#3 @ 5,5: 2x2
+";
+
+    #[test]
+    fn invalid_claim() {
+        let err = parse_input("#1 @ 1,3: 4x4\n#2 @ 3,y: 4x4\n".to_string()).unwrap_err();
+        assert_eq!(err, ParseError::new(2, 8, "y", "a number"));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 4);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 3);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a claim"))
+        );
+    }
+}
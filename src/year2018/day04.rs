@@ -0,0 +1,201 @@
+use core::panic;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+use time::{format_description::well_known::Iso8601, PrimitiveDateTime};
+
+use crate::{
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 4;
+
+    type Parsed = Guards;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<Guards, ParseError> {
+    let mut lines: Vec<_> = parse::lines(&input).non_empty("a record")?.collect();
+    // Sorting the timestamps as strings gives chronological order
+    lines.sort_by_key(|line| line.text);
+    Guards::from_lines(&lines)
+}
+
+pub fn part1(guards: &Guards) -> usize {
+    let sleepy_id = guards.find_most_sleepy();
+    let sleepy_minute = guards
+        .minutes
+        .get(&sleepy_id)
+        .unwrap()
+        .iter()
+        .position_max()
+        .unwrap();
+    sleepy_id * sleepy_minute
+}
+
+pub fn part2(guards: &Guards) -> usize {
+    // TODO: consistent design with part1
+    let mut consistent_id = 0;
+    let mut minute = 0;
+    let mut max_times = 0;
+    for (guard_id, guard_mins) in guards.minutes.iter() {
+        let best_minute = guard_mins.iter().position_max().unwrap();
+        if guard_mins[best_minute] > max_times {
+            max_times = guard_mins[best_minute];
+            consistent_id = *guard_id;
+            minute = best_minute;
+        }
+    }
+    if consistent_id == 0 {
+        panic!("No guard found");
+    }
+    consistent_id * minute
+}
+
+pub struct Guards {
+    minutes: HashMap<usize, [usize; 60]>,
+}
+
+impl Guards {
+    fn new() -> Guards {
+        Guards {
+            minutes: HashMap::new(), // guard_id -> minute -> count asleep
+        }
+    }
+
+    fn from_lines(lines: &[Line]) -> Result<Guards, ParseError> {
+        let mut guards = Guards::new();
+        let mut guard_id = 0;
+        let mut sleep_start: Option<PrimitiveDateTime> = None;
+        for line in lines {
+            let (timestamp, event) = line
+                .span()
+                .split_once("] ")
+                .ok_or_else(|| line.invalid("a record \"[<timestamp>] <event>\""))?;
+            let timestamp = timestamp.trim_start_matches('[');
+            let timestamp =
+                PrimitiveDateTime::parse(&timestamp.text.replace(' ', "T"), &Iso8601::DEFAULT)
+                    .map_err(|_| line.error(timestamp, "a timestamp"))?;
+            let words: Vec<_> = event.split(" ").collect();
+
+            match words.iter().map(|word| word.text).collect::<Vec<_>>()[..] {
+                ["Guard", _, "begins", "shift"] => {
+                    guard_id = line.parse(words[1].trim_start_matches('#'), "a guard id")?;
+                    guards.minutes.entry(guard_id).or_insert([0; 60]);
+                }
+                ["falls", "asleep"] | ["wakes", "up"] if guard_id == 0 => {
+                    return Err(line.error(event, "a guard beginning a shift"));
+                }
+                ["falls", "asleep"] => match sleep_start {
+                    Some(_) => return Err(line.error(event, "\"wakes up\"")),
+                    None => sleep_start = Some(timestamp),
+                },
+                ["wakes", "up"] => {
+                    let start = sleep_start
+                        .take()
+                        .ok_or_else(|| line.error(event, "\"falls asleep\""))?;
+                    let start_min = start.minute() as usize;
+                    let sleep_minutes = (timestamp - start).whole_minutes() as usize;
+                    let guard_minutes = guards.minutes.get_mut(&guard_id).unwrap();
+                    for i in 0..sleep_minutes {
+                        guard_minutes[(start_min + i) % 60] += 1;
+                    }
+                }
+                _ => return Err(line.error(event, "an event")),
+            };
+        }
+        if sleep_start.is_some() {
+            return Err(ParseError::end_of_input(lines.len() + 1, "\"wakes up\""));
+        }
+        Ok(guards)
+    }
+
+    fn find_most_sleepy(&self) -> usize {
+        // TODO: functional?
+        let mut highest = 0;
+        let mut highest_id = 0;
+        for (k, mins) in self.minutes.iter() {
+            let sum = mins.iter().sum();
+            if sum > highest {
+                highest = sum;
+                highest_id = *k;
+            }
+        }
+        highest_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Shuffled, as the records are not in order in the input
+    const EXAMPLE: &str = "\
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-05 00:55] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+";
+
+    #[test]
+    fn wakes_up_without_falling_asleep() {
+        let input = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:25] wakes up
+";
+        let err = parse_input(input.to_string()).err().unwrap();
+        assert_eq!(err, ParseError::new(2, 20, "wakes up", "\"falls asleep\""));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 240);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 4455);
+    }
+
+    #[test]
+    fn empty_or_truncated_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a record"))
+        );
+        let input = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep\n";
+        assert_eq!(
+            parse_input(input.to_string()).err(),
+            Some(ParseError::end_of_input(3, "\"wakes up\""))
+        );
+    }
+}
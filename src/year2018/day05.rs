@@ -0,0 +1,87 @@
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 5;
+
+    type Parsed = String;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+pub fn parse_input(input: String) -> Result<String, ParseError> {
+    // Input is a single-line string, only check that it is made of units
+    let line = parse::lines(&input).expect("a polymer")?;
+    if let Some((i, c)) = line
+        .text
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_alphabetic())
+    {
+        let unit = line.span().slice(i..i + c.len_utf8());
+        return Err(line.error(unit, "a unit (a letter)"));
+    }
+    Ok(input)
+}
+
+pub fn part1(polymer: &str) -> usize {
+    let mut stack: Vec<char> = Vec::new();
+
+    for c in polymer.trim_end().chars() {
+        let last = *stack.last().unwrap_or(&' ');
+        if last.eq_ignore_ascii_case(&c) && last != c {
+            stack.pop();
+        } else {
+            stack.push(c);
+        }
+    }
+
+    stack.len()
+}
+
+pub fn part2(polymer: &str) -> usize {
+    let mut min_len = polymer.len();
+    for c in 'a'..='z' {
+        let improved_polymer = polymer.replace([c, c.to_ascii_uppercase()], "");
+        let len = part1(&improved_polymer);
+        if len < min_len {
+            min_len = len;
+        }
+    }
+
+    min_len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "dabAcCaCBAcCcaDA\n";
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(part1("aA"), 0);
+        assert_eq!(part1("abBA"), 0);
+        assert_eq!(part1("abAB"), 4);
+        assert_eq!(part1("aabAAB"), 6);
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 10);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 4);
+    }
+}
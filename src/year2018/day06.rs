@@ -1,40 +1,51 @@
 use std::{
     cmp::{max, min, Ordering},
     collections::HashSet,
-    fs, usize,
 };
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 6;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day06;
 
-fn main() {
-    let input_file: String = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day06 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 6;
 
-    let sol1 = part1(&parsed_input);
-    let sol2 = part2(&parsed_input);
+    type Parsed = Vec<(usize, usize)>;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
-    println!("Part 1: {sol1}");
-    println!("Part 2: {sol2}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
-fn parse_input(input: String) -> Vec<(usize, usize)> {
-    input
-        .lines()
-        .map(|line: &str| {
-            let parts: Vec<&str> = line.splitn(2, ',').collect();
-            (
-                parts[0].trim().parse::<usize>().unwrap(),
-                parts[1].trim().parse::<usize>().unwrap(),
-            )
+pub fn parse_input(input: String) -> Result<Vec<(usize, usize)>, ParseError> {
+    parse::lines(&input)
+        .non_empty("coordinates")?
+        .map(|line| {
+            let (x, y) = line
+                .span()
+                .split_once(",")
+                .ok_or_else(|| line.invalid("coordinates \"<x>, <y>\""))?;
+            Ok((
+                line.parse(x.trim(), "a number")?,
+                line.parse(y.trim(), "a number")?,
+            ))
         })
         .collect()
 }
 
-fn part1(coords: &[(usize, usize)]) -> usize {
+pub fn part1(coords: &[(usize, usize)]) -> usize {
     let ((xmin, xmax), (ymin, ymax)) = get_range(coords);
 
     // naively iterate over all points in the grid and all centres
@@ -91,7 +102,12 @@ fn get_range(coords: &[(usize, usize)]) -> ((usize, usize), (usize, usize)) {
     ((xmin, xmax), (ymin, ymax))
 }
 
-fn part2(coords: &[(usize, usize)]) -> usize {
+pub fn part2(coords: &[(usize, usize)]) -> usize {
+    region_size(coords, 10_000)
+}
+
+/// Number of points whose total distance to all `coords` is less than `max_distance`
+fn region_size(coords: &[(usize, usize)], max_distance: usize) -> usize {
     let ((xmin, xmax), (ymin, ymax)) = get_range(coords);
 
     let mut c = 0;
@@ -99,10 +115,45 @@ fn part2(coords: &[(usize, usize)]) -> usize {
     for x in xmin..xmax + 1 {
         for y in ymin..ymax + 1 {
             let total_distance = coords.iter().map(|p| l1(*p, (x, y))).sum::<usize>();
-            if total_distance < 10_000 {
+            if total_distance < max_distance {
                 c += 1;
             }
         }
     }
     c
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1, 1
+1, 6
+8, 3
+3, 4
+5, 5
+8, 9
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 17);
+    }
+
+    #[test]
+    fn region_size_example() {
+        assert_eq!(
+            region_size(&parse_input(EXAMPLE.to_string()).unwrap(), 32),
+            16
+        );
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "coordinates"))
+        );
+    }
+}
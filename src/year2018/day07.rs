@@ -0,0 +1,169 @@
+use regex::Regex;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 7;
+
+    type Parsed = HashMap<char, Vec<char>>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<HashMap<char, Vec<char>>, ParseError> {
+    let mut dag_dict = HashMap::new();
+
+    let re = Regex::new(r"^Step (\w) must be finished before step (\w) can begin.$").unwrap();
+    for line in parse::lines(&input).non_empty("a step requirement")? {
+        let caps = re.captures(line.text).ok_or_else(|| {
+            line.invalid("\"Step <X> must be finished before step <Y> can begin.\"")
+        })?;
+        let [parent, child] = [&caps[1], &caps[2]].map(|s| s.chars().next().unwrap());
+        dag_dict.entry(parent).or_insert(Vec::new()).push(child);
+    }
+
+    Ok(dag_dict)
+}
+
+pub fn part1(dag: &HashMap<char, Vec<char>>) -> String {
+    let mut rev_dag = invert_dag(dag);
+
+    // Put the roots in a priority queue
+    // Need Reverse when pushing and .0 when popping to get min heap
+    let mut roots = BinaryHeap::new();
+    for c in dag.keys() {
+        if !rev_dag.contains_key(c) {
+            roots.push(Reverse(c));
+        }
+    }
+
+    let mut topological_sort = String::new();
+    while let Some(root) = roots.pop() {
+        let root = root.0;
+        topological_sort.push(*root);
+        if let Some(children) = dag.get(root) {
+            for c in children {
+                let coparents = rev_dag.get_mut(c).unwrap();
+                coparents.remove(root);
+                if coparents.is_empty() {
+                    roots.push(Reverse(c));
+                }
+            }
+        }
+    }
+
+    topological_sort
+}
+
+pub fn part2(dag: &HashMap<char, Vec<char>>) -> usize {
+    completion_time(dag, 5, 60)
+}
+
+/// Time for `workers` to complete all steps, if step A takes `base_duration + 1`
+fn completion_time(dag: &HashMap<char, Vec<char>>, workers: usize, base_duration: usize) -> usize {
+    let mut rev_dag = invert_dag(dag);
+
+    let mut roots = BinaryHeap::new();
+    for c in dag.keys() {
+        if !rev_dag.contains_key(c) {
+            roots.push(Reverse(c));
+        }
+    }
+    // Reverse((completion time, &letter))
+    let mut in_progress = BinaryHeap::from([Reverse((0, &' '))]);
+
+    let mut t = 0;
+
+    while let Some(step) = in_progress.pop() {
+        let (next, completed) = step.0;
+        t = next;
+        if let Some(children) = dag.get(completed) {
+            for c in children {
+                let coparents = rev_dag.get_mut(c).unwrap();
+                coparents.remove(completed);
+                if coparents.is_empty() {
+                    roots.push(Reverse(c));
+                }
+            }
+        }
+        while !roots.is_empty() && in_progress.len() < workers {
+            let c = roots.pop().unwrap().0;
+            in_progress.push(Reverse((t + base_duration + duration(c), c)))
+        }
+    }
+    t
+}
+
+fn invert_dag<T: Eq + Hash + Clone>(dag: &HashMap<T, Vec<T>>) -> HashMap<T, HashSet<T>> {
+    let mut rev_dag = HashMap::new();
+    for (p, children) in dag.iter() {
+        for c in children {
+            rev_dag
+                .entry(c.clone())
+                .or_insert(HashSet::new())
+                .insert(p.clone());
+        }
+    }
+    rev_dag
+}
+
+fn duration(c: &char) -> usize {
+    *c as usize - 'A' as usize + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), "CABDFE");
+    }
+
+    #[test]
+    fn completion_time_example() {
+        assert_eq!(
+            completion_time(&parse_input(EXAMPLE.to_string()).unwrap(), 2, 0),
+            15
+        );
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a step requirement"))
+        );
+    }
+}
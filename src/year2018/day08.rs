@@ -0,0 +1,126 @@
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 8;
+
+    type Parsed = Node;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+pub fn parse_input(input: String) -> Result<Node, ParseError> {
+    let line = parse::lines(&input).expect("the license numbers")?;
+    let fields: Vec<_> = line.span().split_whitespace().collect();
+    let numbers = fields
+        .iter()
+        .map(|&n| line.parse(n, "a number"))
+        .collect::<Result<Vec<usize>, _>>()?;
+    let (l, root) = tree_from_numbers(&numbers).ok_or_else(|| {
+        let end = line.text.chars().count() + 1;
+        ParseError::new(line.number, end, "", "more numbers")
+    })?;
+    if l < numbers.len() {
+        return Err(line.error(fields[l], "the end of the tree"));
+    }
+    Ok(root)
+}
+
+// Recursively build the tree
+/// Node at the start of `numbers` and count of numbers it uses, or `None`
+/// if there are not enough numbers.
+fn tree_from_numbers(numbers: &[usize]) -> Option<(usize, Node)> {
+    let n_children = *numbers.first()?;
+    let n_data = *numbers.get(1)?;
+
+    let mut children = Vec::with_capacity(n_children);
+    let mut c = 2;
+    for _ in 0..n_children {
+        let (consumed, child) = tree_from_numbers(numbers.get(c..)?)?;
+        c += consumed;
+        children.push(child);
+    }
+
+    let data = numbers.get(c..c + n_data)?.to_vec();
+    c += n_data;
+    Some((c, Node { children, data }))
+}
+
+pub struct Node {
+    children: Vec<Node>,
+    data: Vec<usize>,
+}
+
+impl Node {
+    fn sum(&self) -> usize {
+        let children_sum: usize = self.children.iter().map(|n| n.sum()).sum();
+        let node_sum: usize = self.data.iter().sum();
+        children_sum + node_sum
+    }
+
+    fn value(&self) -> usize {
+        let n = self.children.len();
+        let value = if n == 0 {
+            self.data.iter().sum()
+        } else {
+            self.data
+                .iter()
+                .map(|i| {
+                    if *i < n + 1 {
+                        self.children[*i - 1].value()
+                    } else {
+                        0
+                    }
+                })
+                .sum()
+        };
+        value
+    }
+}
+
+pub fn part1(root: &Node) -> usize {
+    root.sum()
+}
+
+pub fn part2(root: &Node) -> usize {
+    root.value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2\n";
+
+    #[test]
+    fn truncated_tree() {
+        let err = parse_input("2 3 0 3 10 11 12 1 1 0 1\n".to_string())
+            .err()
+            .unwrap();
+        assert_eq!(err, ParseError::new(1, 25, "", "more numbers"));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 138);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 66);
+    }
+}
@@ -0,0 +1,98 @@
+use regex::Regex;
+use std::collections::VecDeque;
+
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 9;
+
+    type Parsed = (usize, usize);
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<(usize, usize), ParseError> {
+    let re = Regex::new(r"(\d+) players; last marble is worth (\d+) points").unwrap();
+    let line = parse::lines(&input).expect("the rules of the game")?;
+    let caps = re
+        .captures(line.text)
+        .ok_or_else(|| line.invalid("\"<n> players; last marble is worth <n> points\""))?;
+    let [n1, n2] = [1, 2].map(|i| line.span().at(caps.get(i).unwrap()));
+    let n_players = line.parse(n1, "a number")?;
+    if n_players == 0 {
+        return Err(line.invalid("a game with at least one player"));
+    }
+    Ok((n_players, line.parse(n2, "a number")?))
+}
+
+pub fn part1((n_players, n_marbles): &(usize, usize)) -> usize {
+    let mut marbles = VecDeque::from([0, 1]);
+    let mut scores = vec![0; *n_players];
+
+    for i in 2..=*n_marbles {
+        if i % 23 == 0 {
+            marbles.rotate_right(7);
+            scores[i % n_players] += i + marbles.remove(0).unwrap();
+        } else {
+            marbles.rotate_left(2);
+            marbles.insert(0, i);
+        }
+    }
+    *scores.iter().max().unwrap()
+}
+
+pub fn part2((n_players, n_marbles): &(usize, usize)) -> usize {
+    let n_marbles = 100 * n_marbles;
+    part1(&(*n_players, n_marbles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_example() {
+        let input = "10 players; last marble is worth 1618 points\n";
+        assert_eq!(parse_input(input.to_string()), Ok((10, 1618)));
+    }
+
+    #[test]
+    fn no_players() {
+        let input = "0 players; last marble is worth 25 points\n";
+        assert_eq!(
+            parse_input(input.to_string()),
+            Err(ParseError::new(
+                1,
+                1,
+                "0 players; last marble is worth 25 points",
+                "a game with at least one player"
+            ))
+        );
+    }
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(part1(&(9, 25)), 32);
+        assert_eq!(part1(&(10, 1618)), 8317);
+        assert_eq!(part1(&(13, 7999)), 146373);
+        assert_eq!(part1(&(17, 1104)), 2764);
+        assert_eq!(part1(&(21, 6111)), 54718);
+        assert_eq!(part1(&(30, 5807)), 37305);
+    }
+}
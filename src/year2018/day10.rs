@@ -0,0 +1,210 @@
+use regex::Regex;
+use std::cmp;
+use std::str::FromStr;
+
+use crate::{
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 10;
+
+    type Parsed = Vec<Point>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<Vec<Point>, ParseError> {
+    parse::lines(&input)
+        .non_empty("a point")?
+        .map(|line| line.parse_with())
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Point {
+    position: (i32, i32),
+    velocity: (i32, i32),
+}
+
+impl FromStr for Point {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::first(s);
+        let rs = r"position=<\s*(-?\d+),\s*(-?\d+)> velocity=<\s*(-?\d+),\s*(-?\d+)";
+        let re = Regex::new(rs).unwrap();
+        let caps = re
+            .captures(s)
+            .ok_or_else(|| line.invalid("\"position=<x, y> velocity=<vx, vy>\""))?;
+        let [x, y, vx, vy] = [1, 2, 3, 4].map(|i| line.span().at(caps.get(i).unwrap()));
+        Ok(Point {
+            position: (line.parse(x, "a number")?, line.parse(y, "a number")?),
+            velocity: (line.parse(vx, "a number")?, line.parse(vy, "a number")?),
+        })
+    }
+}
+
+impl Point {
+    fn evolve(&mut self, n: i32) {
+        self.position.0 += n * self.velocity.0;
+        self.position.1 += n * self.velocity.1;
+    }
+}
+
+pub fn part1(points: &[Point]) -> String {
+    let mut points = points.to_owned();
+    let mut height = get_height(&points);
+
+    loop {
+        for p in &mut points {
+            p.evolve(1);
+        }
+        let new_heigth = get_height(&points);
+        if new_heigth > height {
+            break;
+        }
+        height = new_heigth
+    }
+    for p in &mut points {
+        p.evolve(-1);
+    }
+    draw(&points)
+}
+
+fn range(points: &[Point]) -> (i32, i32, i32, i32) {
+    let mut xmin = i32::MAX;
+    let mut xmax = i32::MIN;
+    let mut ymin = i32::MAX;
+    let mut ymax = i32::MIN;
+    for p in points {
+        xmin = cmp::min(xmin, p.position.0);
+        xmax = cmp::max(xmax, p.position.0);
+        ymin = cmp::min(ymin, p.position.1);
+        ymax = cmp::max(ymax, p.position.1);
+    }
+    (xmin, xmax, ymin, ymax)
+}
+
+fn get_height(points: &[Point]) -> usize {
+    let (_, _, ymin, ymax) = range(points);
+    (ymax - ymin) as usize
+}
+
+/// The points as lines of `#` and spaces.
+fn draw(points: &[Point]) -> String {
+    let (xmin, xmax, ymin, ymax) = range(points);
+
+    let h = (ymax - ymin + 1) as usize;
+    let w = (xmax - xmin + 1) as usize;
+    let mut matrix = vec![vec![' '; w]; h];
+    for p in points {
+        let x = (p.position.0 - xmin) as usize;
+        let y = (p.position.1 - ymin) as usize;
+        matrix[y][x] = '#';
+    }
+    matrix
+        .iter()
+        .map(|line| line.iter().collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn part2(points: &[Point]) -> usize {
+    let mut points = points.to_owned();
+    let mut height = get_height(&points);
+    let mut s = 0;
+
+    loop {
+        for p in &mut points {
+            p.evolve(1);
+        }
+        s += 1;
+        let new_heigth = get_height(&points);
+        if new_heigth > height {
+            break;
+        }
+        height = new_heigth;
+    }
+    s - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>
+";
+
+    #[test]
+    fn part1_example() {
+        let message = "\
+#   #  ###
+#   #   #
+#   #   #
+#####   #
+#   #   #
+#   #   #
+#   #   #
+#   #  ###";
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), message);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 3);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a point"))
+        );
+    }
+}
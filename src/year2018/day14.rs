@@ -1,29 +1,35 @@
-use std::fs;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 14;
+pub struct Day14;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day14 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 14;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = usize;
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
-}
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
 
-fn parse_input(input: String) -> usize {
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+pub fn parse_input(input: String) -> Result<usize, ParseError> {
     // Should return string or Vec of digits to include leading zeros
-    input.lines().next().unwrap().parse().unwrap()
+    let line = parse::lines(&input).expect("a number of recipes")?;
+    line.parse(line.span(), "a number of recipes")
 }
 
-fn part1(input: &usize) -> String {
+pub fn part1(input: &usize) -> String {
     let (mut c1, mut c2) = (0, 1);
     let mut scores = vec![3, 7];
     while scores.len() < input + 10 {
@@ -37,12 +43,12 @@ fn part1(input: &usize) -> String {
         c1 = (c1 + 1 + scores[c1]) % scores.len();
         c2 = (c2 + 1 + scores[c2]) % scores.len();
     }
-    scores[scores.len() - 10..]
+    scores[*input..input + 10]
         .iter()
         .map(|n| n.to_string())
         .collect::<String>()
 }
-fn part2(input: &usize) -> usize {
+pub fn part2(input: &usize) -> usize {
     let target: Vec<usize> = input
         .to_string()
         .chars()
@@ -72,3 +78,24 @@ fn part2(input: &usize) -> usize {
         c2 = (c2 + 1 + scores[c2]) % scores.len();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(part1(&9), "5158916779");
+        assert_eq!(part1(&5), "0124515891");
+        assert_eq!(part1(&18), "9251071085");
+        assert_eq!(part1(&2018), "5941429882");
+    }
+
+    #[test]
+    fn part2_examples() {
+        // 01245 -> 5 is not representable, see parse_input
+        assert_eq!(part2(&51589), 9);
+        assert_eq!(part2(&92510), 18);
+        assert_eq!(part2(&59414), 2018);
+    }
+}
@@ -1,52 +1,84 @@
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fmt;
-use std::fs;
 
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use itertools::Itertools;
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 15;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
 
-const SIZE: usize = 32;
+pub struct Day15;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day15 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 15;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = Grid;
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
+const SIZE: usize = 32;
+
 type Grid = [[CellState; SIZE]; SIZE];
 type Position = (usize, usize);
 
-fn parse_input(input: String) -> Grid {
+pub fn parse_input(input: String) -> Result<Grid, ParseError> {
     let mut grid = [[CellState::Wall; SIZE]; SIZE];
-    for (i, line) in input.lines().enumerate() {
-        for (j, ch) in line.chars().enumerate() {
+    let mut n_lines = 0;
+    for (i, line) in parse::lines(&input)
+        .non_empty("a map of the cavern")?
+        .enumerate()
+    {
+        n_lines += 1;
+        if i >= SIZE {
+            return Err(line.invalid(format!("at most {SIZE} lines")));
+        }
+        for (j, (k, ch)) in line.text.char_indices().enumerate() {
+            if j >= SIZE {
+                return Err(line.error(
+                    line.span().slice(k..line.text.len()),
+                    format!("at most {SIZE} columns"),
+                ));
+            }
             grid[i][j] = match ch {
                 '.' => CellState::Empty,
                 '#' => CellState::Wall,
                 'G' => CellState::Unit(Unit::new('G', (i, j))),
                 'E' => CellState::Unit(Unit::new('E', (i, j))),
-                _ => panic!("Invalid character in input: {} at ({},{})", ch, i, j),
+                _ => {
+                    let ch = line.span().slice(k..k + ch.len_utf8());
+                    return Err(line.error(ch, "a wall, an open cavern or a unit"));
+                }
             };
         }
     }
-    grid
+    // Battles without units never end
+    if !grid
+        .iter()
+        .flatten()
+        .any(|cell| matches!(cell, CellState::Unit(_)))
+    {
+        return Err(ParseError::end_of_input(n_lines + 1, "a unit (G or E)"));
+    }
+    Ok(grid)
 }
 
-fn part1(input: &Grid) -> String {
+pub fn part1(input: &Grid) -> String {
     let (grid, n_rounds, _) = run_battle(input, 3);
 
     let total_hp: u64 = get_units(&grid).iter().map(|unit| unit.hp).sum();
@@ -54,19 +86,19 @@ fn part1(input: &Grid) -> String {
     outcome.to_string()
 }
 
-fn part2(input: &Grid) -> String {
+pub fn part2(input: &Grid) -> String {
     for elf_atk in 4..200 {
-        let (_, _, n_deads) = run_battle(input, elf_atk);
+        let (grid, n_rounds, n_deads) = run_battle(input, elf_atk);
         // print_grid(&grid);
         if n_deads == 0 {
-            return elf_atk.to_string();
+            let total_hp: u64 = get_units(&grid).iter().map(|unit| unit.hp).sum();
+            let outcome = n_rounds * total_hp;
+            return outcome.to_string();
         }
     }
     panic!("The elves cannot win without deaths");
 }
 
-// 20 is not the right answer
-
 fn run_battle(grid: &Grid, elf_atk: u64) -> (Grid, u64, u64) {
     let mut grid = *grid;
 
@@ -81,6 +113,16 @@ fn run_battle(grid: &Grid, elf_atk: u64) -> (Grid, u64, u64) {
                 _ => continue,
             };
 
+            if !grid.iter().any(|row| {
+                row.iter().any(|cell| match cell {
+                    CellState::Unit(u) => u.unit_type != unit.unit_type,
+                    _ => false,
+                })
+            }) {
+                // no enemies left, battle is over before the end of the round
+                return (grid, n_rounds, n_dead_elves);
+            }
+
             // TODO: refactor. Remove get_targets_position and don't look at targets here
             // - after moving, look at neighbors and attack the one with the least HP (if any)
             // - after checking to attack, check if any target is left (no need to get their position)
@@ -120,16 +162,6 @@ fn run_battle(grid: &Grid, elf_atk: u64) -> (Grid, u64, u64) {
                     _ => panic!("No target found at {:},{:}", ti, tj),
                 };
             }
-
-            if !grid.iter().any(|row| {
-                row.iter().any(|cell| match cell {
-                    CellState::Unit(u) => u.unit_type != unit.unit_type,
-                    _ => false,
-                })
-            }) {
-                // no enemies left, battle is over
-                return (grid, n_rounds, n_dead_elves);
-            }
         }
         n_rounds += 1;
     }
@@ -145,7 +177,7 @@ static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
 const MAX_HP: u64 = 200;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-struct Unit {
+pub struct Unit {
     id: usize,
     unit_type: UnitType,
     position: Position,
@@ -169,7 +201,7 @@ impl Unit {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum CellState {
+pub enum CellState {
     Empty,
     Wall,
     Unit(Unit),
@@ -272,3 +304,119 @@ fn neighbor_indices(pos: &Position) -> [Position; 4] {
     // this will only be called with 0 < i, j < SIZE - 1
     [(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, &str, Option<&str>); 6] = [
+        (
+            "\
+#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######
+",
+            "27730",
+            Some("4988"),
+        ),
+        (
+            "\
+#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######
+",
+            "36334",
+            None,
+        ),
+        (
+            "\
+#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######
+",
+            "39514",
+            Some("31284"),
+        ),
+        (
+            "\
+#######
+#E.G#.#
+#.#G..#
+#G.#.G#
+#G..#.#
+#...E.#
+#######
+",
+            "27755",
+            Some("3478"),
+        ),
+        (
+            "\
+#######
+#.E...#
+#.#..G#
+#.###.#
+#E#G#G#
+#...#G#
+#######
+",
+            "28944",
+            Some("6474"),
+        ),
+        (
+            "\
+#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########
+",
+            "18740",
+            Some("1140"),
+        ),
+    ];
+
+    #[test]
+    fn part1_examples() {
+        for (input, outcome, _) in EXAMPLES {
+            assert_eq!(part1(&parse_input(input.to_string()).unwrap()), outcome);
+        }
+    }
+
+    #[test]
+    fn part2_examples() {
+        for (input, _, outcome) in EXAMPLES {
+            if let Some(outcome) = outcome {
+                assert_eq!(part2(&parse_input(input.to_string()).unwrap()), outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a map of the cavern"))
+        );
+        assert_eq!(
+            parse_input("###\n#.#\n###".to_string()).err(),
+            Some(ParseError::end_of_input(4, "a unit (G or E)"))
+        );
+    }
+}
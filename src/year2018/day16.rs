@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use crate::{
+    device18::{
+        self,
+        opcodes::{self, Sample},
+        Machine, Op,
+    },
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 16;
+
+    type Parsed = (Vec<Sample<4>>, Vec<Instr>);
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+type Register = [usize; 4];
+
+/// Sample of three lines: the registers before, the instruction and the
+/// registers after.
+fn parse_sample(s: &str) -> Result<Sample<4>, ParseError> {
+    let mut lines = parse::lines(s);
+    let before = get_registers(lines.expect("\"Before: [a, b, c, d]\"")?, "Before:")?;
+    let instr: Instr = lines.expect("an instruction")?.parse_with()?;
+    let after = get_registers(lines.expect("\"After: [a, b, c, d]\"")?, "After:")?;
+    Ok(Sample {
+        before,
+        opcode: instr.opcode,
+        a: instr.a,
+        b: instr.b,
+        c: instr.c,
+        after,
+    })
+}
+
+/// Instruction with the number of its opcode, whose operation is unknown.
+#[derive(Clone, Copy, Debug)]
+pub struct Instr {
+    opcode: usize,
+    a: usize,
+    b: usize,
+    c: usize,
+}
+
+impl FromStr for Instr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::first(s);
+        let fields: Vec<_> = line.span().trim().split(" ").collect();
+        let [opcode, a, b, c] = fields[..] else {
+            return Err(line.invalid("an instruction \"<opcode> <a> <b> <c>\""));
+        };
+        Ok(Instr {
+            opcode: line.parse(opcode, "an opcode")?,
+            a: line.parse(a, "a number")?,
+            b: line.parse(b, "a number")?,
+            c: line.parse(c, "a number")?,
+        })
+    }
+}
+
+/// Registers of a line `<prefix> [a, b, c, d]`.
+fn get_registers(line: Line, prefix: &str) -> Result<Register, ParseError> {
+    let expected = format!("\"{prefix} [a, b, c, d]\"");
+    let values = line
+        .span()
+        .strip_prefix(prefix)
+        .and_then(|s| s.trim_start().strip_prefix("[")?.strip_suffix("]"))
+        .ok_or_else(|| line.invalid(&expected))?;
+    let values = values
+        .split(", ")
+        .map(|v| line.parse(v, "a register value"))
+        .collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|_| line.invalid(expected))
+}
+
+pub fn parse_input(input: String) -> Result<(Vec<Sample<4>>, Vec<Instr>), ParseError> {
+    let Some((samples, program)) = input.split_once("\n\n\n") else {
+        let end = input.lines().count() + 1;
+        return Err(ParseError::end_of_input(
+            end,
+            "a program after 3 empty lines",
+        ));
+    };
+
+    let mut parsed = Vec::new();
+    let mut offset = 0;
+    for sample in samples.split("\n\n") {
+        parsed.push(parse_sample(sample).map_err(|err| err.offset(offset))?);
+        offset += sample.lines().count() + 1;
+    }
+    // The program starts with the last of the empty lines
+    let offset = samples.lines().count() + 2;
+    let program = parse::lines(program)
+        .filter(|line| !line.text.is_empty())
+        .map(|line| {
+            line.parse_with()
+                .map_err(|err: ParseError| err.offset(offset))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((parsed, program))
+}
+
+impl Instr {
+    fn with_op(self, op: Op) -> device18::Instr {
+        device18::Instr {
+            op,
+            a: self.a,
+            b: self.b,
+            c: self.c,
+        }
+    }
+}
+
+pub fn part1((samples, _): &(Vec<Sample<4>>, Vec<Instr>)) -> usize {
+    samples
+        .iter()
+        .filter(|sample| sample.matching(&Op::ALL).len() >= 3)
+        .count()
+}
+
+pub fn part2((samples, program): &(Vec<Sample<4>>, Vec<Instr>)) -> usize {
+    let identification = opcodes::identify(&Op::ALL, samples);
+    let ops = identification
+        .mapping()
+        .unwrap_or_else(|| panic!("Opcodes not identified:\n{identification}"));
+
+    let program: Vec<_> = program
+        .iter()
+        .map(|instr| instr.with_op(ops[&instr.opcode]))
+        .collect();
+    let mut machine = Machine::<4>::new(None, &program);
+    machine.run();
+    machine.registers[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]";
+
+    #[test]
+    fn matching_ops_example() {
+        let sample = parse_sample(SAMPLE).unwrap();
+        assert_eq!(sample.matching(&Op::ALL), [Op::Addi, Op::Mulr, Op::Seti]);
+    }
+
+    #[test]
+    fn invalid_program() {
+        let input = format!("{SAMPLE}\n\n{SAMPLE}\n\n\n\n9 2 1 2\n9 2 x 2\n");
+        let err = parse_input(input).unwrap_err();
+        assert_eq!(err, ParseError::new(12, 5, "x", "a number"));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = format!("{SAMPLE}\n\n\n\n9 2 1 2\n");
+        assert_eq!(part1(&parse_input(input).unwrap()), 1);
+    }
+}
@@ -1,39 +1,46 @@
 use std::{
     cmp::{max, min},
     collections::HashSet,
-    fs,
     str::FromStr,
 };
 
 use itertools::Itertools;
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 17;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day17;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day17 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 17;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = Vec<Line>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
-fn parse_input(input: String) -> Vec<Line> {
-    input
-        .lines()
-        .map(|line| Line::from_str(line).unwrap())
+pub fn parse_input(input: String) -> Result<Vec<Line>, ParseError> {
+    parse::lines(&input)
+        .non_empty("a vein")?
+        .map(|line| line.parse_with())
         .collect()
 }
 
 #[derive(Debug)]
-enum Line {
+pub enum Line {
     Horizontal((usize, usize), usize),
     Vertical(usize, (usize, usize)),
 }
@@ -48,32 +55,35 @@ impl Line {
 }
 
 impl FromStr for Line {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((p, range)) = s
+        let line = parse::Line::first(s);
+        let (p, range) = line
+            .span()
             .split(", ")
-            .map(|s| s.split('=').last().unwrap())
+            .map(|s| s.split_once("="))
             .collect_tuple()
-        {
-            let p = p.parse().unwrap();
-            if let Some((rmin, rmax)) = range
-                .split("..")
-                .map(|s| s.parse().unwrap())
-                .collect_tuple()
-            {
-                return match s.chars().next() {
-                    Some('x') => Ok(Line::Vertical(p, (rmin, rmax))),
-                    Some('y') => Ok(Line::Horizontal((rmin, rmax), p)),
-                    _ => Err(()),
-                };
-            }
+            .and_then(|(p, range)| p.zip(range))
+            .ok_or_else(|| {
+                line.invalid("a vein \"x=<x>, y=<y1>..<y2>\" or \"y=<y>, x=<x1>..<x2>\"")
+            })?;
+        let (axis, p) = p;
+        let p = line.parse(p, "a number")?;
+        let (rmin, rmax) = range
+            .1
+            .split_once("..")
+            .ok_or_else(|| line.error(range.1, "a range \"<min>..<max>\""))?;
+        let range = (line.parse(rmin, "a number")?, line.parse(rmax, "a number")?);
+        match axis.text {
+            "x" => Ok(Line::Vertical(p, range)),
+            "y" => Ok(Line::Horizontal(range, p)),
+            _ => Err(line.error(axis, "x or y")),
         }
-        Err(())
     }
 }
 
-fn part1(clay_lines: &[Line]) -> usize {
+pub fn part1(clay_lines: &[Line]) -> usize {
     let (mut xmin, mut xmax) = (usize::MAX, usize::MIN);
     let (mut ymin, mut ymax) = (usize::MAX, usize::MIN);
     for line in clay_lines {
@@ -152,15 +162,13 @@ fn flow(x0: usize, y0: usize, grid: &mut Vec<Vec<char>>) {
 
     if let (Some(xl), Some(xr)) = (left_wall, right_wall) {
         // two walls, fill with ~
-        for x in xl + 1..xr {
-            grid[y0][x] = '~';
-        }
+        grid[y0][xl + 1..xr].fill('~');
         grid[y0 - 1][x0] = '|';
         flow(x0, y0 - 1, grid);
     }
 }
 
-fn part2(clay_lines: &[Line]) -> usize {
+pub fn part2(clay_lines: &[Line]) -> usize {
     let (mut xmin, mut xmax) = (usize::MAX, usize::MIN);
     let (mut ymin, mut ymax) = (usize::MAX, usize::MIN);
     for line in clay_lines {
@@ -183,6 +191,7 @@ fn part2(clay_lines: &[Line]) -> usize {
     // fix incomplete filling of rows
     // TODO: fix the filling algorithm instead
     for y in 1..ymax {
+        #[allow(clippy::needless_range_loop)]
         for x in xmin..xmax {
             if grid[y][x] == '|' && grid[y - 1][x] == '~' {
                 grid[y][x] = '~';
@@ -195,3 +204,37 @@ fn part2(clay_lines: &[Line]) -> usize {
         .map(|row| row.iter().filter(|&&c| c == '~').count())
         .sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 57);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 29);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a vein"))
+        );
+    }
+}
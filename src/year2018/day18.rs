@@ -1,37 +1,76 @@
-use std::{cmp::min, collections::HashMap, fs};
+use std::{cmp::min, collections::HashMap};
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 18;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
 
-const SIZE: usize = 50;
-type Grid = [[char; SIZE]; SIZE];
+pub struct Day18;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day18 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 18;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = Grid;
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
-fn parse_input(input: String) -> Grid {
+const SIZE: usize = 50;
+type Grid = [[char; SIZE]; SIZE];
+
+pub fn parse_input(input: String) -> Result<Grid, ParseError> {
+    // The area is square, as wide as the first line
     let mut grid = [['x'; SIZE]; SIZE];
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
+    let mut width = 0;
+    let mut n_lines = 0;
+    for (i, line) in parse::lines(&input)
+        .non_empty("a map of the area")?
+        .enumerate()
+    {
+        if i >= SIZE {
+            return Err(line.invalid(format!("at most {SIZE} lines")));
+        }
+        n_lines += 1;
+        if i == 0 {
+            width = line.text.chars().count();
+        } else if line.text.chars().count() != width {
+            return Err(line.invalid(format!("a line of {width} acres")));
+        }
+        for (j, (k, c)) in line.text.char_indices().enumerate() {
+            if j >= SIZE {
+                return Err(line.error(
+                    line.span().slice(k..line.text.len()),
+                    format!("at most {SIZE} columns"),
+                ));
+            }
+            if !matches!(c, '.' | '|' | '#') {
+                let c = line.span().slice(k..k + c.len_utf8());
+                return Err(line.error(c, "an acre (., | or #)"));
+            }
             grid[j][i] = c;
         }
     }
-    grid
+    if n_lines < width {
+        return Err(ParseError::end_of_input(
+            n_lines + 1,
+            format!("a line of {width} acres"),
+        ));
+    }
+    Ok(grid)
 }
 
-fn part1(grid: &Grid) -> usize {
+pub fn part1(grid: &Grid) -> usize {
     let mut grid = *grid;
     for _ in 0..10 {
         grid = step(&grid);
@@ -40,7 +79,7 @@ fn part1(grid: &Grid) -> usize {
     estimate_value(&grid)
 }
 
-fn part2(grid: &Grid) -> usize {
+pub fn part2(grid: &Grid) -> usize {
     let mut grid = *grid;
     let mut seen = HashMap::new();
 
@@ -101,7 +140,10 @@ fn neighbours(i: usize, j: usize, grid: &Grid) -> HashMap<char, u8> {
     for (nj, row) in grid.iter().enumerate().take(ymax + 1).skip(ymin) {
         for (ni, c) in row.iter().enumerate().take(xmax + 1).skip(xmin) {
             if (ni, nj) != (i, j) {
-                *counts.get_mut(c).unwrap() += 1;
+                // Cells outside of the map are not counted
+                if let Some(count) = counts.get_mut(c) {
+                    *count += 1;
+                }
             }
         }
     }
@@ -136,9 +178,49 @@ fn step(grid: &Grid) -> Grid {
                         '.'
                     }
                 }
+                'x' => 'x', // outside of the map
                 _ => panic!("Not a valid map"),
             }
         }
     }
     new_grid
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+.#.#...|#.
+.....#|##|
+.|..|...#.
+..|#.....#
+#.#|||#|#|
+...#.||...
+.|....|...
+||...#|.#|
+|.||||..|.
+...#.|..|.
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 1147);
+    }
+
+    #[test]
+    fn empty_or_truncated_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a map of the area"))
+        );
+        assert_eq!(
+            parse_input("...\n..".to_string()).err(),
+            Some(ParseError::new(2, 1, "..", "a line of 3 acres"))
+        );
+        assert_eq!(
+            parse_input("...\n...".to_string()).err(),
+            Some(ParseError::end_of_input(3, "a line of 3 acres"))
+        );
+    }
+}
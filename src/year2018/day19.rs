@@ -0,0 +1,61 @@
+pub use crate::device18::parse_input;
+use crate::device18::{optimise::Optimised, Instr, Machine};
+use crate::{parse::ParseError, Answer, Solution};
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 19;
+
+    type Parsed = (usize, Vec<Instr>);
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn part1((ip, instructions): &(usize, Vec<Instr>)) -> usize {
+    let mut machine = Machine::<6>::new(Some(*ip), instructions);
+    machine.run();
+    machine.registers[0]
+}
+
+pub fn part2((ip, instructions): &(usize, Vec<Instr>)) -> usize {
+    // The program computes the sum of the divisors of a number, with two
+    // nested loops trying every product, which the optimiser replaces
+    let program = Optimised::new(*ip, instructions);
+    let mut machine = program.machine();
+    machine.registers[0] = 1;
+    program.run(&mut machine);
+    machine.registers[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 6);
+    }
+}
@@ -1,37 +1,61 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
 };
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 20;
+pub struct Day20;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day20 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 20;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = String;
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
-fn parse_input(input: String) -> String {
-    input
+pub fn parse_input(input: String) -> Result<String, ParseError> {
+    let line = parse::lines(&input).expect("a route")?;
+    let route = line
+        .span()
         .trim_start_matches('^')
         .trim_end()
-        .trim_end_matches('$')
-        .to_string()
+        .trim_end_matches('$');
+    let mut depth = 0;
+    for (i, c) in route.text.char_indices() {
+        depth += match c {
+            'N' | 'S' | 'E' | 'W' | '|' => 0,
+            '(' => 1,
+            ')' if depth > 0 => -1,
+            ')' => return Err(line.error(route.slice(i..i + 1), "a direction or (")),
+            _ => {
+                let c = route.slice(i..i + c.len_utf8());
+                return Err(line.error(c, "a direction, (, | or )"));
+            }
+        };
+    }
+    if depth > 0 {
+        let end = route.slice(route.text.len()..route.text.len());
+        return Err(line.error(end, "a closing parenthesis"));
+    }
+    Ok(route.text.to_string())
 }
 
 type Coord = (i32, i32);
 
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     let graph = build_graph(input);
 
     let mut d = 0;
@@ -54,7 +78,7 @@ fn part1(input: &str) -> usize {
     d
 }
 
-fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> usize {
     let graph = build_graph(input);
 
     let mut d = 0;
@@ -85,7 +109,7 @@ fn split_directions(s: &str) -> Vec<String> {
     let mut depth = 0;
     let mut options = Vec::new();
     let mut start = 1;
-    for (i, c) in s.chars().enumerate() {
+    for (i, c) in s.char_indices() {
         match c {
             '(' => depth += 1,
             '|' if depth == 1 => {
@@ -116,7 +140,7 @@ fn build_graph(input: &str) -> HashMap<Coord, HashSet<Coord>> {
     let mut seen = HashSet::new();
     while let Some((mut coord, directions)) = queue.pop() {
         seen.insert((coord, directions.clone()));
-        for (i, c) in directions.chars().enumerate() {
+        for (i, c) in directions.char_indices() {
             let new_coord = match c {
                 'N' => (coord.0, coord.1 + 1),
                 'S' => (coord.0, coord.1 - 1),
@@ -139,3 +163,33 @@ fn build_graph(input: &str) -> HashMap<Coord, HashSet<Coord>> {
     }
     graph
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, usize); 5] = [
+        ("^WNE$", 3),
+        ("^ENWWW(NEEE|SSE(EE|N))$", 10),
+        ("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$", 18),
+        ("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$", 23),
+        (
+            "^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$",
+            31,
+        ),
+    ];
+
+    #[test]
+    fn part1_examples() {
+        for (input, doors) in EXAMPLES {
+            assert_eq!(part1(&parse_input(input.to_string()).unwrap()), doors);
+        }
+    }
+
+    #[test]
+    fn part2_examples() {
+        for (input, _) in EXAMPLES {
+            assert_eq!(part2(&parse_input(input.to_string()).unwrap()), 0);
+        }
+    }
+}
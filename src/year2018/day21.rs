@@ -0,0 +1,99 @@
+pub use crate::device18::parse_input;
+use crate::device18::{cycle, halting, optimise::Optimised, Instr};
+use crate::{parse::ParseError, Answer, Solution};
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 21;
+
+    type Parsed = (usize, Vec<Instr>);
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+/// Values of r0 halting the program, in order of number of instructions.
+fn halting_values<'p>(program: &'p Optimised) -> impl Iterator<Item = usize> + 'p {
+    halting::halting_values(program, 0)
+        .unwrap_or_else(|err| panic!("Unsupported program: {err}"))
+        .map(|halting| halting.unwrap_or_else(|fault| panic!("{fault}")).value)
+}
+
+pub fn part1((ip, instructions): &(usize, Vec<Instr>)) -> usize {
+    // r0 doesn't actually enter the computation, but only the exit condition
+    // comparing it with another register: if we get there, that register is
+    // the answer
+    let program = Optimised::new(*ip, instructions);
+    let first = halting_values(&program).next();
+    first.expect("Program halted without comparing with r0")
+}
+
+pub fn part2((ip, instructions): &(usize, Vec<Instr>)) -> usize {
+    // The program halts the first time r0 equals the value compared with it,
+    // so the one taking the most instructions is the last new value before
+    // the program starts repeating itself. r0 is set to a value never reached
+    // so that the program keeps running until then.
+    let checks = halting::checks(*ip, instructions, 0)
+        .unwrap_or_else(|err| panic!("Unsupported program: {err}"));
+    let [check] = checks[..] else {
+        panic!(
+            "Expected a single comparison with r0, found {}",
+            checks.len()
+        );
+    };
+    let program = Optimised::new(*ip, instructions);
+    let mut machine = program.machine();
+    machine.registers[0] = usize::MAX;
+    cycle::find(
+        &mut machine,
+        |m| program.try_step(m),
+        check.pc,
+        check.register,
+    )
+    .unwrap_or_else(|fault| panic!("{fault}"))
+    .and_then(|cycle| cycle.last())
+    .expect("Program halted without repeating itself")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r0 is compared with 3 * r1 % 8 for r1 in 1, 2, ... 7, 0, 1, ...
+    const PROGRAM: &str = "\
+#ip 4
+seti 0 0 1
+addi 1 1 1
+bani 1 7 1
+muli 1 3 3
+bani 3 7 3
+eqrr 3 0 2
+addr 2 4 4
+seti 0 0 4
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(PROGRAM.to_string()).unwrap()), 3);
+    }
+
+    #[test]
+    fn part2_example() {
+        let parsed = parse_input(PROGRAM.to_string()).unwrap();
+        assert_eq!(part2(&parsed), 0);
+        // Same as the last value found by the halting analysis
+        let program = Optimised::new(parsed.0, &parsed.1);
+        assert_eq!(halting_values(&program).last(), Some(0));
+    }
+}
@@ -1,54 +1,59 @@
 use std::{
     cmp::{min, Reverse},
     collections::HashMap,
-    fs,
 };
 
-use itertools::Itertools;
 use priority_queue::PriorityQueue;
 
-const YEAR: u16 = 2018;
-const DAY: u8 = 22;
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day22;
 
-fn main() {
-    let input_file = format!("inputs/{YEAR}_{DAY:02}.txt");
-    let input = fs::read_to_string(input_file).expect("Input file not found");
-    let parsed_input = parse_input(input);
+impl Solution for Day22 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 22;
 
-    println!("{YEAR} day {DAY}");
-    println!("================");
+    type Parsed = (usize, (usize, usize));
 
-    let sol1 = part1(&parsed_input);
-    println!("Part 1: {sol1}");
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
 
-    let sol2 = part2(&parsed_input);
-    println!("Part 2: {sol2}");
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
-fn parse_input(input: String) -> (usize, (usize, usize)) {
-    let mut lines = input.lines();
-    let depth = lines
-        .next()
-        .unwrap()
-        .trim_start_matches("depth: ")
-        .parse()
-        .unwrap();
-    let target = lines
-        .next()
-        .unwrap()
-        .trim_start_matches("target: ")
-        .split(',')
-        .map(|x| x.parse().unwrap())
-        .collect_tuple()
-        .unwrap();
-    (depth, target)
+pub fn parse_input(input: String) -> Result<(usize, (usize, usize)), ParseError> {
+    let mut lines = parse::lines(&input);
+    let line = lines.expect("the depth")?;
+    let depth = line
+        .span()
+        .strip_prefix("depth: ")
+        .ok_or_else(|| line.invalid("\"depth: <depth>\""))?;
+    let depth = line.parse(depth, "a number")?;
+    let line = lines.expect("the target")?;
+    let (x, y) = line
+        .span()
+        .strip_prefix("target: ")
+        .and_then(|target| target.split_once(","))
+        .ok_or_else(|| line.invalid("\"target: <x>,<y>\""))?;
+    let target = (line.parse(x, "a number")?, line.parse(y, "a number")?);
+    Ok((depth, target))
 }
 
-fn part1((depth, target): &(usize, (usize, usize))) -> usize {
+pub fn part1((depth, target): &(usize, (usize, usize))) -> usize {
     build_grid(depth, target, &(0, 0)).iter().flatten().sum()
 }
 
-fn part2((depth, target): &(usize, (usize, usize))) -> usize {
+pub fn part2((depth, target): &(usize, (usize, usize))) -> usize {
     // let depth = &7305;
     // let target = &(13, 734);
     // let depth = &510;
@@ -80,15 +85,8 @@ fn part2((depth, target): &(usize, (usize, usize))) -> usize {
         }
     }
     min(
-        *dist
-            .get(&(target.0, target.1, 1))
-            .or(Some(&usize::MAX))
-            .unwrap(),
-        *dist
-            .get(&(target.0, target.1, 2))
-            .or(Some(&usize::MAX))
-            .unwrap()
-            + 7,
+        *dist.get(&(target.0, target.1, 1)).unwrap_or(&usize::MAX),
+        *dist.get(&(target.0, target.1, 2)).unwrap_or(&usize::MAX) + 7,
     )
 }
 
@@ -116,7 +114,7 @@ fn build_grid(depth: &usize, target: &(usize, usize), extra: &(usize, usize)) ->
 
 type Node = (usize, usize, usize);
 
-fn get_neighbors(node: &Node, grid: &Vec<Vec<usize>>) -> HashMap<Node, usize> {
+fn get_neighbors(node: &Node, grid: &[Vec<usize>]) -> HashMap<Node, usize> {
     let (x, y, tool) = *node;
     let mut grid_neighbors = Vec::new();
     if x > 0 {
@@ -145,3 +143,23 @@ fn get_neighbors(node: &Node, grid: &Vec<Vec<usize>>) -> HashMap<Node, usize> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+depth: 510
+target: 10,10
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), 114);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse_input(EXAMPLE.to_string()).unwrap()), 45);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{cmp::Reverse, collections::BinaryHeap, str::FromStr};
+
+use regex::Regex;
+
+use crate::{
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 23;
+
+    type Parsed = Vec<Nanobot>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<Vec<Nanobot>, ParseError> {
+    parse::lines(&input)
+        .non_empty("a nanobot")?
+        .map(|line| line.parse_with())
+        .collect()
+}
+
+pub fn part1(nanobots: &[Nanobot]) -> usize {
+    let strongest = nanobots.iter().max_by_key(|n| n.r).unwrap();
+    nanobots
+        .iter()
+        .filter(|n| strongest.distance(n.coord) <= strongest.r)
+        .count()
+}
+
+/// Distance from the origin of the closest point in range of the most
+/// nanobots.
+///
+/// Searches cubes, best first: the number of nanobots in range of any point
+/// of a cube is an upper bound for each of its points, and its distance from
+/// the origin a lower bound, so the first cube of a single point is the
+/// answer. Cubes are split in eight until then.
+pub fn part2(nanobots: &[Nanobot]) -> usize {
+    let mut min_coord = [i64::MAX; 3];
+    let mut max_coord = [i64::MIN; 3];
+    for n in nanobots {
+        for i in 0..3 {
+            min_coord[i] = min_coord[i].min(n.coord[i] - n.r).min(0);
+            max_coord[i] = max_coord[i].max(n.coord[i] + n.r).max(0);
+        }
+    }
+    let span = (0..3)
+        .map(|i| max_coord[i] - min_coord[i] + 1)
+        .max()
+        .unwrap_or(1);
+    let mut size = 1;
+    while size < span {
+        size *= 2;
+    }
+
+    let cube = |min: [i64; 3], size: i64| {
+        let in_range = nanobots
+            .iter()
+            .filter(|n| distance_to_cube(n.coord, min, size) <= n.r)
+            .count();
+        let distance = distance_to_cube([0; 3], min, size);
+        (in_range, Reverse(distance), Reverse(size), min)
+    };
+    let mut queue = BinaryHeap::from([cube(min_coord, size)]);
+    while let Some((_, Reverse(distance), Reverse(size), min)) = queue.pop() {
+        if size == 1 {
+            return distance as usize;
+        }
+        let half = size / 2;
+        for corner in 0..8 {
+            let min = [0, 1, 2].map(|i| min[i] + half * (corner >> i & 1));
+            queue.push(cube(min, half));
+        }
+    }
+    unreachable!("The queue only runs out after a cube of a single point")
+}
+
+/// Distance from `point` to the closest point of the cube of side `size`
+/// from `min`.
+fn distance_to_cube(point: [i64; 3], min: [i64; 3], size: i64) -> i64 {
+    (0..3)
+        .map(|i| {
+            (min[i] - point[i])
+                .max(point[i] - (min[i] + size - 1))
+                .max(0)
+        })
+        .sum()
+}
+
+pub struct Nanobot {
+    coord: [i64; 3],
+    r: i64,
+}
+
+impl FromStr for Nanobot {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::first(s);
+        let re = Regex::new(r"pos=<(-?\d+),(-?\d+),(-?\d+)>, r=(\d+)").unwrap();
+        let cap = re
+            .captures(s)
+            .ok_or_else(|| line.invalid("\"pos=<x,y,z>, r=<r>\""))?;
+        let [x, y, z, r] = [1, 2, 3, 4].map(|i| line.span().at(cap.get(i).unwrap()));
+        let coord = [
+            line.parse(x, "a number")?,
+            line.parse(y, "a number")?,
+            line.parse(z, "a number")?,
+        ];
+        let r = line.parse(r, "a number")?;
+
+        Ok(Nanobot { coord, r })
+    }
+}
+
+impl Nanobot {
+    fn distance(&self, point: [i64; 3]) -> i64 {
+        self.coord
+            .iter()
+            .zip(point.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        let input = "\
+pos=<0,0,0>, r=4
+pos=<1,0,0>, r=1
+pos=<4,0,0>, r=3
+pos=<0,2,0>, r=1
+pos=<0,5,0>, r=3
+pos=<0,0,3>, r=1
+pos=<1,1,1>, r=1
+pos=<1,1,2>, r=1
+pos=<1,3,1>, r=1
+";
+        assert_eq!(part1(&parse_input(input.to_string()).unwrap()), 7);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = "\
+pos=<10,12,12>, r=2
+pos=<12,14,12>, r=2
+pos=<16,12,12>, r=4
+pos=<14,14,14>, r=6
+pos=<50,50,50>, r=200
+pos=<10,10,10>, r=5
+";
+        assert_eq!(part2(&parse_input(input.to_string()).unwrap()), 36);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a nanobot"))
+        );
+    }
+}
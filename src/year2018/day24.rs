@@ -0,0 +1,325 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    iter::repeat,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use itertools::Itertools;
+
+use crate::{
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 24;
+
+    type Parsed = (Army, Army);
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        match part2(parsed) {
+            Some(remaining_units) => remaining_units.to_string().into(),
+            None => "no boost makes the immune system win".into(),
+        }
+    }
+}
+
+pub fn parse_input(input: String) -> Result<(Army, Army), ParseError> {
+    let mut lines = parse::lines(&input);
+    let line = lines.expect("\"Immune System:\"")?;
+    if line.text != "Immune System:" {
+        return Err(line.invalid("\"Immune System:\""));
+    }
+    let immune_system = lines
+        .by_ref()
+        .take_while(|line| !line.text.is_empty())
+        .map(|line| line.parse_with())
+        .collect::<Result<_, _>>()?;
+
+    let line = lines.expect("\"Infection:\"")?;
+    if line.text != "Infection:" {
+        return Err(line.invalid("\"Infection:\""));
+    }
+    let infection = lines
+        .map(|line| line.parse_with())
+        .collect::<Result<_, _>>()?;
+
+    Ok((immune_system, infection))
+}
+
+pub fn part1((immune_system, infection): &(Army, Army)) -> String {
+    let (_, remaining_units) = fight(immune_system, infection, 0);
+    remaining_units.to_string()
+}
+
+/// Units left with the smallest boost of the attack of the immune system
+/// making it win, if a boost up to the most hit points of all the infection
+/// groups together does: then a single unit destroys any group it can
+/// damage in one attack.
+pub fn part2((immune_system, infection): &(Army, Army)) -> Option<u64> {
+    let max_boost = infection.iter().map(|g| g.units * g.hp).max().unwrap_or(0);
+    (0..=max_boost).find_map(|boost| match fight(immune_system, infection, boost) {
+        (Some(ArmyType::ImmuneSystem), remaining_units) => Some(remaining_units),
+        _ => None,
+    })
+}
+
+/// Fight with the attack of each immune system group increased by `boost`,
+/// until one army is destroyed or a round kills no unit. Returns the winner,
+/// `None` in a stalemate, and the number of units left.
+fn fight(immune_system: &Army, infection: &Army, boost: u64) -> (Option<ArmyType>, u64) {
+    let mut immune_system = immune_system.clone();
+    let mut infection = infection.clone();
+    for group in &mut immune_system {
+        group.attack += boost;
+    }
+
+    while !immune_system.is_empty() && !infection.is_empty() {
+        let target_map = select_targets(&immune_system, &infection)
+            .into_iter()
+            .chain(select_targets(&infection, &immune_system))
+            .collect::<HashMap<_, _>>();
+
+        let attack_order: Vec<_> = immune_system
+            .iter()
+            .zip(repeat(ArmyType::ImmuneSystem))
+            .chain(infection.iter().zip(repeat(ArmyType::Infection)))
+            .sorted_by_key(|(group, _)| Reverse(group.initiative))
+            .map(|(group, army)| (group.id, army))
+            .collect();
+
+        let mut to_remove = HashSet::new();
+        let mut killed = 0;
+        for (attacker_id, side) in attack_order {
+            // TODO: make side a field of Group
+            let (attacking_army, defending_army) = match side {
+                ArmyType::ImmuneSystem => (&immune_system, &mut infection),
+                ArmyType::Infection => (&infection, &mut immune_system),
+            };
+            // TODO: Armies as hashmaps
+            let attacker = attacking_army
+                .iter()
+                .find(|g| g.id == attacker_id)
+                .expect("Attacker ID not found");
+            if attacker.units == 0 {
+                continue;
+            }
+            if let Some(target_id) = target_map.get(&attacker_id).expect("Attacker ID not found") {
+                let target = defending_army
+                    .iter_mut()
+                    .find(|g| g.id == *target_id)
+                    .expect("Target ID not found");
+                let units_lost = (damage(attacker, target) / target.hp).min(target.units);
+                killed += units_lost;
+                target.units -= units_lost;
+                if target.units == 0 {
+                    to_remove.insert(target.id);
+                }
+            }
+        }
+
+        immune_system.retain(|g| !to_remove.contains(&g.id));
+        infection.retain(|g| !to_remove.contains(&g.id));
+        if killed == 0 {
+            let remaining_units = total_units(&immune_system) + total_units(&infection);
+            return (None, remaining_units);
+        }
+    }
+    let winner = match immune_system.is_empty() {
+        true => ArmyType::Infection,
+        false => ArmyType::ImmuneSystem,
+    };
+    let remaining_units = total_units(&immune_system) + total_units(&infection);
+    (Some(winner), remaining_units)
+}
+
+type AttackType = String; // Could be enum, but types are not specified outside input.
+
+#[derive(Clone, Debug)]
+pub struct Group {
+    id: usize,
+    units: u64,
+    hp: u64,
+    attack: u64,
+    attack_type: AttackType,
+    initiative: u64,
+    weaknesses: HashSet<AttackType>,
+    immunities: HashSet<AttackType>,
+}
+
+impl Group {
+    fn effective_power(&self) -> u64 {
+        self.attack * self.units
+    }
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+impl FromStr for Group {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::first(s);
+        let units_hp_re = regex::Regex::new(r"(\d+) units each with (\d+) hit points").unwrap();
+        let attack_re =
+            regex::Regex::new(r"with an attack that does (\d+) (\w+) damage at initiative (\d+)")
+                .unwrap();
+        let weaknesses_immunities_re = regex::Regex::new(r"\((.*?)\)").unwrap();
+
+        let units_hp_caps = units_hp_re
+            .captures(s)
+            .ok_or_else(|| line.invalid("\"<n> units each with <n> hit points\""))?;
+        let attack_caps = attack_re.captures(s).ok_or_else(|| {
+            line.invalid("\"with an attack that does <n> <type> damage at initiative <n>\"")
+        })?;
+        let number = |caps: &regex::Captures, i| {
+            line.parse(line.span().at(caps.get(i).unwrap()), "a number")
+        };
+
+        let units = number(&units_hp_caps, 1)?;
+        let hp = number(&units_hp_caps, 2)?;
+        let attack = number(&attack_caps, 1)?;
+        let attack_type = attack_caps[2].to_string();
+        let initiative = number(&attack_caps, 3)?;
+
+        let mut weaknesses = HashSet::new();
+        let mut immunities = HashSet::new();
+
+        if let Some(caps) = weaknesses_immunities_re.captures(s) {
+            let details = &caps[1];
+            for detail in details.split("; ") {
+                if let Some(stripped) = detail.strip_prefix("weak to ") {
+                    weaknesses.extend(stripped.split(", ").map(String::from));
+                } else if let Some(stripped) = detail.strip_prefix("immune to ") {
+                    immunities.extend(stripped.split(", ").map(String::from));
+                }
+            }
+        }
+
+        Ok(Group {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            units,
+            hp,
+            attack,
+            attack_type,
+            initiative,
+            weaknesses,
+            immunities,
+        })
+    }
+}
+
+type Army = Vec<Group>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArmyType {
+    ImmuneSystem,
+    Infection,
+}
+
+fn select_targets(attackers: &Army, defendants: &Army) -> HashMap<usize, Option<usize>> {
+    let mut attackers = attackers.clone();
+    attackers.sort_by_key(|group| Reverse((group.effective_power(), group.initiative)));
+    let mut defendants = defendants.clone();
+
+    let mut target_map = HashMap::new();
+
+    for attacker in attackers {
+        let target = match defendants.iter().max_by_key(|target| {
+            (
+                damage(&attacker, target),
+                target.effective_power(),
+                target.initiative,
+            )
+        }) {
+            Some(target) if damage(&attacker, target) > 0 => Some(target.id),
+            _ => None,
+        };
+        if let Some(target_id) = target {
+            // target selected, remove from available targets
+            defendants.retain(|d| d.id != target_id);
+        }
+        target_map.insert(attacker.id, target);
+    }
+    target_map
+}
+
+fn damage(attacker: &Group, target: &Group) -> u64 {
+    if target.immunities.contains(&attacker.attack_type) {
+        0
+    } else if target.weaknesses.contains(&attacker.attack_type) {
+        2 * attacker.effective_power()
+    } else {
+        attacker.effective_power()
+    }
+}
+
+fn total_units(army: &Army) -> u64 {
+    army.iter().map(|g| g.units).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Immune System:
+17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
+989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
+
+Infection:
+801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
+4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse_input(EXAMPLE.to_string()).unwrap()), "5216");
+    }
+
+    #[test]
+    fn part2_example() {
+        let armies = parse_input(EXAMPLE.to_string()).unwrap();
+        assert_eq!(part2(&armies), Some(51));
+        let (immune_system, infection) = &armies;
+        assert_eq!(
+            fight(immune_system, infection, 1570).0,
+            Some(ArmyType::ImmuneSystem)
+        );
+        assert_eq!(
+            fight(immune_system, infection, 1569).0,
+            Some(ArmyType::Infection)
+        );
+    }
+
+    #[test]
+    fn stalemate() {
+        // Each army is immune to the attacks of the other
+        let armies = parse_input(
+            "\
+Immune System:
+10 units each with 10 hit points (immune to slashing) with an attack that does 5 fire damage at initiative 1
+
+Infection:
+10 units each with 10 hit points (immune to fire) with an attack that does 5 slashing damage at initiative 2
+"
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(fight(&armies.0, &armies.1, 0), (None, 20));
+        assert_eq!(part2(&armies), None);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{
+    parse::{self, ParseError},
+    Answer, Solution,
+};
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 25;
+
+    type Parsed = Vec<Coord>;
+
+    fn parse(input: String) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+pub fn parse_input(input: String) -> Result<Vec<Coord>, ParseError> {
+    parse::lines(&input)
+        .non_empty("a point")?
+        .map(|line| {
+            let values = line
+                .span()
+                .split(",")
+                .map(|num| line.parse(num.trim(), "a number"))
+                .collect::<Result<Vec<i32>, _>>()?;
+            values
+                .try_into()
+                .map_err(|_| line.invalid("a point \"<a>,<b>,<c>,<d>\""))
+        })
+        .collect()
+}
+
+pub fn part1(points: &[Coord]) -> usize {
+    let mut constellations = Vec::<Constellation>::new();
+    for p in points.iter().sorted() {
+        let mut new_constellation = constellations
+            .iter()
+            .filter(|c| is_in_constellation(p, c))
+            .fold(HashSet::new(), |acc, c| acc.union(c).cloned().collect());
+
+        if !new_constellation.is_empty() {
+            constellations.retain(|c| !is_in_constellation(p, c));
+        }
+        new_constellation.insert(*p);
+        constellations.push(new_constellation);
+    }
+    constellations.len()
+}
+
+pub fn part2(_: &[Coord]) -> &'static str {
+    // There is no part 2 on the last day
+    "******"
+}
+
+type Coord = [i32; 4];
+
+type Constellation = HashSet<Coord>;
+
+fn is_in_constellation(p1: &Coord, c: &Constellation) -> bool {
+    c.iter().any(|p2| manhattan_distance(p1, p2) <= 3)
+}
+
+fn manhattan_distance(p1: &Coord, p2: &Coord) -> i32 {
+    p1.iter()
+        .zip(p2.iter())
+        .map(|(x1, x2)| (x1 - x2).abs())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, usize); 4] = [
+        (
+            "\
+0,0,0,0
+3,0,0,0
+0,3,0,0
+0,0,3,0
+0,0,0,3
+0,0,0,6
+9,0,0,0
+12,0,0,0
+",
+            2,
+        ),
+        (
+            "\
+-1,2,2,0
+0,0,2,-2
+0,0,0,-2
+-1,2,0,0
+-2,-2,-2,2
+3,0,2,-1
+-1,3,2,2
+-1,0,-1,0
+0,2,1,-2
+3,0,0,0
+",
+            4,
+        ),
+        (
+            "\
+1,-1,0,1
+2,0,-1,0
+3,2,-1,0
+0,0,3,1
+0,0,-1,-1
+2,3,-2,0
+-2,2,0,0
+2,-2,0,-1
+1,-1,0,-1
+3,2,0,2
+",
+            3,
+        ),
+        (
+            "\
+1,-1,-1,-2
+-2,-2,0,1
+0,2,1,3
+-2,3,-2,1
+0,2,3,-2
+-1,-1,1,-2
+0,-2,-1,0
+-2,2,3,-1
+1,2,2,0
+-1,-2,0,-2
+",
+            8,
+        ),
+    ];
+
+    #[test]
+    fn part1_examples() {
+        for (input, constellations) in EXAMPLES {
+            assert_eq!(
+                part1(&parse_input(input.to_string()).unwrap()),
+                constellations
+            );
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            parse_input(String::new()).err(),
+            Some(ParseError::end_of_input(1, "a point"))
+        );
+    }
+}